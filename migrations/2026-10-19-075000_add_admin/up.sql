@@ -0,0 +1,2 @@
+ALTER TABLE user
+    ADD is_admin BOOLEAN NOT NULL DEFAULT 0;
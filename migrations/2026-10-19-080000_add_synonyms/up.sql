@@ -0,0 +1,48 @@
+CREATE TABLE synonym
+(
+    synonym_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    group_id   INTEGER      NOT NULL,
+    term       VARCHAR(255) NOT NULL UNIQUE,
+    created_at REAL DEFAULT (datetime('now', 'localtime'))
+);
+
+-- the first term of a group is the one every other term is indexed as
+INSERT INTO synonym(group_id, term)
+VALUES (1, 'yoghurt'),
+       (1, 'joghurt'),
+       (1, 'yogurt'),
+       (2, 'courgette'),
+       (2, 'zucchini'),
+       (3, 'phyllo'),
+       (3, 'filo'),
+       (4, 'rutabaga'),
+       (4, 'swede'),
+       (5, 'chili'),
+       (5, 'chilli'),
+       (6, 'porcini'),
+       (6, 'steinpilz'),
+       (6, 'cep'),
+       (7, 'hummus'),
+       (7, 'houmus'),
+       (8, 'aubergine'),
+       (8, 'eggplant'),
+       (9, 'zaatar'),
+       (9, 'za''atar'),
+       (9, 'zatar'),
+       (10, 'soy'),
+       (10, 'soya'),
+       (11, 'cornstarch'),
+       (11, 'cornflour'),
+       (12, 'savoiardi'),
+       (12, 'ladyfingers'),
+       (13, 'verjuice'),
+       (13, 'verjus'),
+       (14, 'squash'),
+       (14, 'pumpkin'),
+       (15, 'makrut'),
+       (15, 'kaffir'),
+       (16, 'cornichon'),
+       (16, 'gherkin'),
+       (17, 'scallion'),
+       (17, 'spring onion'),
+       (17, 'green onion');
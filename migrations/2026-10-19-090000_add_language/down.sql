@@ -0,0 +1,4 @@
+ALTER TABLE recipe
+    DROP COLUMN language;
+ALTER TABLE book
+    DROP COLUMN language;
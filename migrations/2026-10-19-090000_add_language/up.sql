@@ -0,0 +1,4 @@
+ALTER TABLE book
+    ADD language VARCHAR(8);
+ALTER TABLE recipe
+    ADD language VARCHAR(8);
@@ -0,0 +1,10 @@
+CREATE TABLE saved_search
+(
+    saved_search_id  INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id          INTEGER      NOT NULL REFERENCES user (id),
+    search_name      VARCHAR(255) NOT NULL,
+    query            TEXT         NOT NULL,
+    smart_collection BOOLEAN      NOT NULL DEFAULT 0,
+    created_at       REAL DEFAULT (datetime('now', 'localtime')),
+    UNIQUE (user_id, search_name)
+);
@@ -0,0 +1,3 @@
+DROP TABLE collection_share;
+DROP TABLE collection_recipe;
+DROP TABLE collection;
@@ -0,0 +1,26 @@
+CREATE TABLE collection
+(
+    collection_id   INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id         INTEGER      NOT NULL REFERENCES user (id),
+    collection_name VARCHAR(255) NOT NULL,
+    created_at      REAL DEFAULT (datetime('now', 'localtime')),
+    UNIQUE (user_id, collection_name)
+);
+
+CREATE TABLE collection_recipe
+(
+    collection_id INTEGER NOT NULL REFERENCES collection (collection_id) ON DELETE CASCADE,
+    recipe_id     INTEGER NOT NULL REFERENCES recipe (recipe_id) ON DELETE CASCADE,
+    position      INTEGER NOT NULL,
+    note          TEXT,
+    created_at    REAL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (collection_id, recipe_id)
+);
+
+CREATE TABLE collection_share
+(
+    collection_id INTEGER NOT NULL REFERENCES collection (collection_id) ON DELETE CASCADE,
+    user_id       INTEGER NOT NULL REFERENCES user (id),
+    created_at    REAL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (collection_id, user_id)
+);
@@ -0,0 +1,14 @@
+CREATE TABLE tag
+(
+    tag_id     INTEGER PRIMARY KEY AUTOINCREMENT,
+    tag_name   VARCHAR(255) NOT NULL UNIQUE,
+    created_at REAL DEFAULT (datetime('now', 'localtime'))
+);
+
+CREATE TABLE recipe_tag
+(
+    recipe_id  INTEGER NOT NULL REFERENCES recipe (recipe_id),
+    tag_id     INTEGER NOT NULL REFERENCES tag (tag_id),
+    created_at REAL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (recipe_id, tag_id)
+);
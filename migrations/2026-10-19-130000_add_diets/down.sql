@@ -0,0 +1,3 @@
+ALTER TABLE ingredient DROP COLUMN classified;
+DROP TABLE recipe_diet_override;
+DROP TABLE ingredient_attribute;
@@ -0,0 +1,62 @@
+CREATE TABLE ingredient_attribute
+(
+    ingredient_id INTEGER      NOT NULL REFERENCES ingredient (id),
+    attribute     VARCHAR(32) NOT NULL,
+    created_at    REAL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (ingredient_id, attribute)
+);
+
+CREATE TABLE recipe_diet_override
+(
+    recipe_id  INTEGER     NOT NULL REFERENCES recipe (recipe_id),
+    diet       VARCHAR(32) NOT NULL,
+    is_diet    BOOLEAN     NOT NULL,
+    created_at REAL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (recipe_id, diet)
+);
+
+-- a head start for ingredients that are already there, the rest is set on /admin/ingredients
+INSERT OR IGNORE INTO ingredient_attribute(ingredient_id, attribute)
+SELECT id, 'meat'
+FROM ingredient
+WHERE lower(name) IN ('chicken', 'beef', 'pork', 'lamb', 'veal', 'bacon', 'ham', 'sausage', 'chorizo', 'duck', 'turkey',
+                      'mince', 'pancetta', 'prosciutto', 'salami', 'merguez');
+INSERT OR IGNORE INTO ingredient_attribute(ingredient_id, attribute)
+SELECT id, 'fish'
+FROM ingredient
+WHERE lower(name) IN ('fish', 'salmon', 'tuna', 'cod', 'anchovy', 'anchovies', 'prawn', 'prawns', 'shrimp', 'mussels',
+                      'squid', 'sardine', 'sardines', 'mackerel', 'trout', 'fish sauce');
+INSERT OR IGNORE INTO ingredient_attribute(ingredient_id, attribute)
+SELECT id, 'dairy'
+FROM ingredient
+WHERE lower(name) IN ('milk', 'butter', 'cream', 'yoghurt', 'yogurt', 'cheese', 'feta', 'parmesan', 'mozzarella',
+                      'ricotta', 'creme fraiche', 'sour cream', 'mascarpone', 'halloumi', 'labneh', 'goat cheese');
+INSERT OR IGNORE INTO ingredient_attribute(ingredient_id, attribute)
+SELECT id, 'egg'
+FROM ingredient
+WHERE lower(name) IN ('egg', 'eggs', 'egg yolk', 'egg white', 'mayonnaise');
+INSERT OR IGNORE INTO ingredient_attribute(ingredient_id, attribute)
+SELECT id, 'honey'
+FROM ingredient
+WHERE lower(name) IN ('honey');
+INSERT OR IGNORE INTO ingredient_attribute(ingredient_id, attribute)
+SELECT id, 'gluten'
+FROM ingredient
+WHERE lower(name) IN ('flour', 'bread', 'pasta', 'couscous', 'bulgur', 'barley', 'spelt', 'breadcrumbs', 'noodles',
+                      'puff pastry', 'pastry', 'soy sauce', 'orzo', 'farro', 'semolina');
+INSERT OR IGNORE INTO ingredient_attribute(ingredient_id, attribute)
+SELECT id, 'nuts'
+FROM ingredient
+WHERE lower(name) IN ('almond', 'almonds', 'walnut', 'walnuts', 'hazelnut', 'hazelnuts', 'pistachio', 'pistachios',
+                      'cashew', 'cashews', 'pecan', 'pecans', 'pine nuts', 'peanut', 'peanuts', 'peanut butter');
+INSERT OR IGNORE INTO ingredient_attribute(ingredient_id, attribute)
+SELECT id, 'alcohol'
+FROM ingredient
+WHERE lower(name) IN ('wine', 'white wine', 'red wine', 'beer', 'brandy', 'rum', 'sherry', 'vermouth', 'marsala');
+
+-- an ingredient without attributes is either plain or not looked at yet, only classified ones count towards diets
+ALTER TABLE ingredient ADD COLUMN classified BOOLEAN NOT NULL DEFAULT 0;
+
+UPDATE ingredient
+SET classified = 1
+WHERE id IN (SELECT ingredient_id FROM ingredient_attribute);
@@ -0,0 +1,2 @@
+DROP TABLE cooked;
+DROP TABLE recipe_rating;
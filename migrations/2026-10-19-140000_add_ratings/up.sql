@@ -0,0 +1,20 @@
+CREATE TABLE recipe_rating
+(
+    user_id    INTEGER NOT NULL REFERENCES user (id),
+    recipe_id  INTEGER NOT NULL REFERENCES recipe (recipe_id) ON DELETE CASCADE,
+    rating     INTEGER CHECK (rating BETWEEN 1 AND 5),
+    cook_again BOOLEAN NOT NULL DEFAULT 0,
+    created_at REAL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (user_id, recipe_id)
+);
+
+CREATE TABLE cooked
+(
+    cooked_id  INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id    INTEGER NOT NULL REFERENCES user (id),
+    recipe_id  INTEGER NOT NULL REFERENCES recipe (recipe_id) ON DELETE CASCADE,
+    cooked_on  TEXT    NOT NULL DEFAULT (date('now', 'localtime')),
+    created_at REAL DEFAULT (datetime('now', 'localtime'))
+);
+
+CREATE INDEX cooked_recipe ON cooked (recipe_id, cooked_on);
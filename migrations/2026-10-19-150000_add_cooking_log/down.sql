@@ -0,0 +1,3 @@
+ALTER TABLE cooked DROP COLUMN photo;
+ALTER TABLE cooked DROP COLUMN servings;
+ALTER TABLE cooked DROP COLUMN notes;
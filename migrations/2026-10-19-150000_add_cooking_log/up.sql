@@ -0,0 +1,3 @@
+ALTER TABLE cooked ADD COLUMN notes TEXT;
+ALTER TABLE cooked ADD COLUMN servings INTEGER;
+ALTER TABLE cooked ADD COLUMN photo TEXT;
@@ -0,0 +1,10 @@
+CREATE TABLE recipe_image
+(
+    image_id   INTEGER PRIMARY KEY AUTOINCREMENT,
+    recipe_id  INTEGER NOT NULL REFERENCES recipe (recipe_id) ON DELETE CASCADE,
+    file_name  TEXT    NOT NULL,
+    is_cover   BOOLEAN NOT NULL DEFAULT 0,
+    position   INTEGER NOT NULL,
+    created_at REAL DEFAULT (datetime('now', 'localtime')),
+    UNIQUE (recipe_id, file_name)
+);
@@ -0,0 +1,3 @@
+ALTER TABLE user DROP COLUMN display_name;
+ALTER TABLE recipe_comment DROP COLUMN modified_at;
+ALTER TABLE recipe_comment DROP COLUMN parent_id;
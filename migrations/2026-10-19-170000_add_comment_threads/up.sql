@@ -0,0 +1,3 @@
+ALTER TABLE recipe_comment ADD COLUMN parent_id INTEGER REFERENCES recipe_comment (comment_id);
+ALTER TABLE recipe_comment ADD COLUMN modified_at TEXT;
+ALTER TABLE user ADD COLUMN display_name VARCHAR(255);
@@ -0,0 +1,2 @@
+ALTER TABLE recipe DROP COLUMN modified_at;
+DROP TABLE recipe_revision;
@@ -0,0 +1,23 @@
+-- Full state of a recipe after each edit, the first row of a recipe holds the state before its first tracked edit
+CREATE TABLE recipe_revision
+(
+    revision_id    INTEGER PRIMARY KEY AUTOINCREMENT,
+    recipe_id      INTEGER NOT NULL REFERENCES recipe (recipe_id) ON DELETE CASCADE,
+    user_id        INTEGER REFERENCES user (id),
+    recipe_name    VARCHAR(255),
+    primary_season INTEGER NOT NULL,
+    course_id      INTEGER NOT NULL,
+    book_id        INTEGER,
+    page           INTEGER,
+    recipe_url     TEXT,
+    language       VARCHAR(2),
+    ingredients    TEXT    NOT NULL,
+    tags           TEXT    NOT NULL,
+    content        TEXT    NOT NULL,
+    created_at     TEXT    NOT NULL DEFAULT (datetime('now', 'localtime'))
+);
+
+CREATE INDEX recipe_revision_recipe ON recipe_revision (recipe_id);
+
+-- set whenever an edit is saved
+ALTER TABLE recipe ADD COLUMN modified_at TEXT;
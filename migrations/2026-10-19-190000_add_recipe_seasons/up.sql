@@ -0,0 +1,10 @@
+-- seasons besides recipe.primary_season, a row for the primary season only carries its months
+CREATE TABLE recipe_season
+(
+    recipe_id   INTEGER NOT NULL REFERENCES recipe (recipe_id) ON DELETE CASCADE,
+    season_id   INTEGER NOT NULL REFERENCES season (season_id),
+    start_month INTEGER CHECK (start_month BETWEEN 1 AND 12),
+    end_month   INTEGER CHECK (end_month BETWEEN 1 AND 12),
+    created_at  REAL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (recipe_id, season_id)
+);
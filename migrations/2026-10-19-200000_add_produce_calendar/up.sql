@@ -0,0 +1,46 @@
+-- months an ingredient is in season, per region, the region in use is configured with PRODUCE_REGION
+CREATE TABLE produce_month
+(
+    ingredient_id INTEGER     NOT NULL REFERENCES ingredient (id),
+    region        VARCHAR(64) NOT NULL,
+    month         INTEGER     NOT NULL CHECK (month BETWEEN 1 AND 12),
+    created_at    REAL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (ingredient_id, region, month)
+);
+
+-- a head start for central Europe, the rest is set on /admin/produce
+WITH months(month) AS (VALUES (7), (8), (9), (10))
+INSERT OR IGNORE INTO produce_month(ingredient_id, region, month)
+SELECT id, 'central-europe', month
+FROM ingredient, months
+WHERE lower(name) IN ('tomato', 'tomatoes', 'aubergine', 'eggplant', 'pepper', 'peppers', 'bell pepper', 'courgette',
+                      'zucchini', 'cucumber', 'sweetcorn', 'corn', 'green beans', 'fennel');
+WITH months(month) AS (VALUES (6), (7), (8))
+INSERT OR IGNORE INTO produce_month(ingredient_id, region, month)
+SELECT id, 'central-europe', month
+FROM ingredient, months
+WHERE lower(name) IN ('strawberries', 'strawberry', 'cherries', 'raspberries', 'blueberries', 'apricot', 'apricots',
+                      'peas', 'broad beans', 'basil', 'new potatoes');
+WITH months(month) AS (VALUES (8), (9), (10))
+INSERT OR IGNORE INTO produce_month(ingredient_id, region, month)
+SELECT id, 'central-europe', month
+FROM ingredient, months
+WHERE lower(name) IN ('plums', 'plum', 'figs', 'fig', 'peach', 'peaches', 'nectarines', 'grapes', 'blackberries');
+WITH months(month) AS (VALUES (9), (10), (11), (12), (1), (2))
+INSERT OR IGNORE INTO produce_month(ingredient_id, region, month)
+SELECT id, 'central-europe', month
+FROM ingredient, months
+WHERE lower(name) IN ('pumpkin', 'squash', 'butternut squash', 'apple', 'apples', 'pear', 'pears', 'beetroot',
+                      'celeriac', 'parsnip', 'parsnips', 'red cabbage', 'chestnuts', 'walnuts', 'quince');
+WITH months(month) AS (VALUES (11), (12), (1), (2), (3))
+INSERT OR IGNORE INTO produce_month(ingredient_id, region, month)
+SELECT id, 'central-europe', month
+FROM ingredient, months
+WHERE lower(name) IN ('kale', 'brussels sprouts', 'leek', 'leeks', 'savoy cabbage', 'lamb''s lettuce', 'chicory',
+                      'black salsify', 'swede', 'turnip', 'blood orange', 'oranges', 'mandarins');
+WITH months(month) AS (VALUES (4), (5), (6))
+INSERT OR IGNORE INTO produce_month(ingredient_id, region, month)
+SELECT id, 'central-europe', month
+FROM ingredient, months
+WHERE lower(name) IN ('asparagus', 'wild garlic', 'rhubarb', 'radish', 'radishes', 'spring onion', 'spring onions',
+                      'spinach', 'lettuce', 'new carrots');
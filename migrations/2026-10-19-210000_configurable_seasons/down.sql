@@ -0,0 +1,4 @@
+ALTER TABLE season DROP COLUMN all_year;
+ALTER TABLE season DROP COLUMN position;
+ALTER TABLE season DROP COLUMN end_month;
+ALTER TABLE season DROP COLUMN start_month;
@@ -0,0 +1,20 @@
+-- seasons and occasions are data now, months decide which season is current and are empty for things like Ramadan
+ALTER TABLE season ADD COLUMN start_month INTEGER CHECK (start_month BETWEEN 1 AND 12);
+ALTER TABLE season ADD COLUMN end_month INTEGER CHECK (end_month BETWEEN 1 AND 12);
+ALTER TABLE season ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+-- recipes filed under an all year season are in season at any time
+ALTER TABLE season ADD COLUMN all_year BOOLEAN NOT NULL DEFAULT 0;
+
+-- the seasons the code used to know about, recipes refer to them by id
+INSERT OR IGNORE INTO season(season_id, tag_name)
+VALUES (1, 'Summer'),
+       (2, 'Autumn'),
+       (3, 'Winter'),
+       (4, 'Spring'),
+       (5, 'Independent');
+UPDATE season SET start_month = 6, end_month = 8 WHERE season_id = 1;
+UPDATE season SET start_month = 9, end_month = 11 WHERE season_id = 2;
+UPDATE season SET start_month = 12, end_month = 2 WHERE season_id = 3;
+UPDATE season SET start_month = 3, end_month = 5 WHERE season_id = 4;
+UPDATE season SET position = season_id;
+UPDATE season SET all_year = 1 WHERE season_id = 5;
@@ -0,0 +1,12 @@
+-- read as "recipe_id is a variant of / is served with / uses as a component linked_recipe_id"
+CREATE TABLE recipe_link
+(
+    recipe_id        INTEGER     NOT NULL REFERENCES recipe (recipe_id) ON DELETE CASCADE,
+    linked_recipe_id INTEGER     NOT NULL REFERENCES recipe (recipe_id) ON DELETE CASCADE,
+    link_type        VARCHAR(16) NOT NULL CHECK (link_type IN ('variant_of', 'serve_with', 'component')),
+    created_at       REAL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (recipe_id, linked_recipe_id, link_type),
+    CHECK (recipe_id != linked_recipe_id)
+);
+
+CREATE INDEX recipe_link_linked_recipe_id ON recipe_link (linked_recipe_id);
@@ -0,0 +1,4 @@
+ALTER TABLE recipe DROP COLUMN servings;
+ALTER TABLE recipe_ingredient DROP COLUMN amount_grams;
+DROP TABLE ingredient_nutrient;
+DROP TABLE nutrient;
@@ -0,0 +1,25 @@
+-- values per 100 g as imported from a nutrient database like USDA FoodData Central or CIQUAL
+CREATE TABLE nutrient
+(
+    nutrient_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source      VARCHAR(32)  NOT NULL,
+    source_code VARCHAR(64)  NOT NULL,
+    food_name   VARCHAR(255) NOT NULL,
+    energy_kcal REAL,
+    protein_g   REAL,
+    fat_g       REAL,
+    carbs_g     REAL,
+    fibre_g     REAL,
+    created_at  REAL DEFAULT (datetime('now', 'localtime')),
+    UNIQUE (source, source_code)
+);
+
+CREATE TABLE ingredient_nutrient
+(
+    ingredient_id INTEGER PRIMARY KEY REFERENCES ingredient (id) ON DELETE CASCADE,
+    nutrient_id   INTEGER NOT NULL REFERENCES nutrient (nutrient_id) ON DELETE CASCADE,
+    created_at    REAL DEFAULT (datetime('now', 'localtime'))
+);
+
+ALTER TABLE recipe_ingredient ADD COLUMN amount_grams REAL CHECK (amount_grams >= 0);
+ALTER TABLE recipe ADD COLUMN servings INTEGER CHECK (servings > 0);
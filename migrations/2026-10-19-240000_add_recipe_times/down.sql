@@ -0,0 +1,9 @@
+ALTER TABLE recipe_revision DROP COLUMN difficulty;
+ALTER TABLE recipe_revision DROP COLUMN total_minutes;
+ALTER TABLE recipe_revision DROP COLUMN cook_minutes;
+ALTER TABLE recipe_revision DROP COLUMN prep_minutes;
+ALTER TABLE recipe_revision DROP COLUMN servings;
+ALTER TABLE recipe DROP COLUMN difficulty;
+ALTER TABLE recipe DROP COLUMN total_minutes;
+ALTER TABLE recipe DROP COLUMN cook_minutes;
+ALTER TABLE recipe DROP COLUMN prep_minutes;
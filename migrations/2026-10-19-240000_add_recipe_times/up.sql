@@ -0,0 +1,10 @@
+ALTER TABLE recipe ADD COLUMN prep_minutes INTEGER CHECK (prep_minutes >= 0);
+ALTER TABLE recipe ADD COLUMN cook_minutes INTEGER CHECK (cook_minutes >= 0);
+ALTER TABLE recipe ADD COLUMN total_minutes INTEGER CHECK (total_minutes >= 0);
+ALTER TABLE recipe ADD COLUMN difficulty VARCHAR(16) CHECK (difficulty IN ('easy', 'medium', 'hard'));
+
+ALTER TABLE recipe_revision ADD COLUMN servings INTEGER;
+ALTER TABLE recipe_revision ADD COLUMN prep_minutes INTEGER;
+ALTER TABLE recipe_revision ADD COLUMN cook_minutes INTEGER;
+ALTER TABLE recipe_revision ADD COLUMN total_minutes INTEGER;
+ALTER TABLE recipe_revision ADD COLUMN difficulty VARCHAR(16);
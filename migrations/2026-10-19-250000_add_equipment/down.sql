@@ -0,0 +1,3 @@
+DROP TABLE user_equipment;
+DROP TABLE recipe_equipment;
+DROP TABLE equipment;
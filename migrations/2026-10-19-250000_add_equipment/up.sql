@@ -0,0 +1,32 @@
+CREATE TABLE equipment
+(
+    equipment_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name         VARCHAR(64) NOT NULL UNIQUE,
+    created_at   REAL DEFAULT (datetime('now', 'localtime'))
+);
+
+INSERT INTO equipment(name)
+VALUES ('BBQ'),
+       ('Blender'),
+       ('Food processor'),
+       ('Ice-cream machine'),
+       ('Pasta machine'),
+       ('Pressure cooker'),
+       ('Slow cooker'),
+       ('Stand mixer');
+
+CREATE TABLE recipe_equipment
+(
+    recipe_id    INTEGER NOT NULL REFERENCES recipe (recipe_id) ON DELETE CASCADE,
+    equipment_id INTEGER NOT NULL REFERENCES equipment (equipment_id) ON DELETE CASCADE,
+    created_at   REAL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (recipe_id, equipment_id)
+);
+
+CREATE TABLE user_equipment
+(
+    user_id      INTEGER NOT NULL REFERENCES user (id) ON DELETE CASCADE,
+    equipment_id INTEGER NOT NULL REFERENCES equipment (equipment_id) ON DELETE CASCADE,
+    created_at   REAL DEFAULT (datetime('now', 'localtime')),
+    PRIMARY KEY (user_id, equipment_id)
+);
@@ -0,0 +1,15 @@
+-- price paid for `quantity` units of an ingredient, the newest entry counts and older ones are kept as history
+CREATE TABLE ingredient_price
+(
+    price_id      INTEGER PRIMARY KEY AUTOINCREMENT,
+    ingredient_id INTEGER     NOT NULL REFERENCES ingredient (id) ON DELETE CASCADE,
+    price         REAL        NOT NULL CHECK (price >= 0),
+    quantity      REAL        NOT NULL DEFAULT 1 CHECK (quantity > 0),
+    unit          VARCHAR(8)  NOT NULL CHECK (unit IN ('kg', 'g', 'l', 'ml', 'piece')),
+    piece_grams   REAL CHECK (piece_grams > 0),
+    currency      VARCHAR(3)  NOT NULL,
+    priced_on     VARCHAR(10) NOT NULL,
+    created_at    REAL DEFAULT (datetime('now', 'localtime'))
+);
+
+CREATE INDEX ingredient_price_ingredient_id ON ingredient_price (ingredient_id);
@@ -1,32 +1,123 @@
-use serde::Deserialize;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::diet::EDiet;
+use crate::recipe_time::EDifficulty;
 
 #[derive(Deserialize)]
 pub struct RecipePrefill {
     pub course: Option<i32>,
     pub book: Option<i32>,
-    pub season: Option<usize>,
+    pub season: Option<i32>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 #[derive(Default, Clone)]
 pub struct SearchPrefill {
     pub name: Option<String>,
     pub season: Option<i32>,
     pub course: Option<i32>,
     pub book: Option<i32>,
+    // bookmarked urls may leave it out
+    #[serde(default)]
     pub tried: i32,
-    pub season1: Option<i32>,
-    pub season2: Option<i32>,
-    pub season3: Option<i32>,
-    pub season4: Option<i32>,
-    pub season5: Option<i32>,
+    // one `season<id>=1` pair per checked season, read and written by hand below
+    #[serde(skip)]
+    pub seasons: Vec<i32>,
     pub legacy: Option<i32>,
-
-
+    pub fuzzy: Option<i32>,
+    pub collection: Option<i32>,
+    pub tag: Option<String>,
+    pub vegetarian: Option<i32>,
+    pub vegan: Option<i32>,
+    pub gluten_free: Option<i32>,
+    pub dairy_free: Option<i32>,
+    pub nut_free: Option<i32>,
+    pub sort: Option<String>,
+    pub min_minutes: Option<i32>,
+    pub max_minutes: Option<i32>,
+    pub max_difficulty: Option<String>,
+    pub equipped: Option<i32>,
+    // per serving, in whatever single currency the recipe is priced in
+    pub max_cost: Option<f32>,
 }
 
 impl SearchPrefill {
     pub fn template_name(&self)  -> String {
         self.name.as_ref().unwrap_or(&"".to_string()).clone()
     }
+
+    pub fn has_season(&self, season_id: i32) -> bool {
+        self.seasons.contains(&season_id)
+    }
+
+    pub fn max_difficulty(&self) -> Option<EDifficulty> {
+        self.max_difficulty.as_deref().and_then(EDifficulty::from_code)
+    }
+
+    pub fn has_diet(&self, diet: EDiet) -> bool {
+        let selected = match diet {
+            EDiet::Vegetarian => self.vegetarian,
+            EDiet::Vegan => self.vegan,
+            EDiet::GlutenFree => self.gluten_free,
+            EDiet::DairyFree => self.dairy_free,
+            EDiet::NutFree => self.nut_free,
+        };
+        selected.is_some()
+    }
+
+    /// The query string of the `GET /search` url that runs this search again.
+    pub fn to_query_string(&self) -> String {
+        let season_pairs: Vec<(String, &str)> = self.seasons.iter().map(|x| (format!("season{}", x), "1")).collect();
+        [serde_urlencoded::to_string(self).unwrap_or_default(), serde_urlencoded::to_string(season_pairs).unwrap_or_default()].iter()
+            .filter(|x| !x.is_empty())
+            .join("&")
+    }
+
+    pub fn from_query_string(query: &str) -> Option<SearchPrefill> {
+        let pairs: Vec<(String, String)> = serde_urlencoded::from_str(query).ok()?;
+        // number inputs left empty are sent as `key=`, which would not parse
+        let filled: Vec<&(String, String)> = pairs.iter().filter(|x| !x.1.is_empty()).collect();
+        let mut res: SearchPrefill = serde_urlencoded::from_str(serde_urlencoded::to_string(filled).ok()?.as_str()).ok()?;
+        res.seasons = pairs.iter()
+            .filter(|(_, value)| !value.is_empty())
+            .filter_map(|(key, _)| key.strip_prefix("season")?.parse::<i32>().ok())
+            .unique()
+            .collect();
+        Some(res)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::args::SearchPrefill;
+    use crate::recipe_time::EDifficulty;
+
+    #[test]
+    fn query_string_round_trip() {
+        let prefill = SearchPrefill { name: Some("lentil soup".to_string()), book: Some(2), tried: 1, seasons: vec![3, 12], ..Default::default() };
+        let query = prefill.to_query_string();
+        assert_eq!("name=lentil+soup&book=2&tried=1&season3=1&season12=1", query);
+        let res = SearchPrefill::from_query_string(&query).unwrap();
+        assert_eq!(prefill.name, res.name);
+        assert_eq!(prefill.book, res.book);
+        assert!(res.has_season(3) && res.has_season(12));
+        assert_eq!("tried=0&season5=1", SearchPrefill::from_query_string("season5=1&seasonx=1&season7=").unwrap().to_query_string());
+    }
+
+    #[test]
+    fn empty_numbers_are_left_out() {
+        let res = SearchPrefill::from_query_string("name=&min_minutes=&max_minutes=30&max_difficulty=medium&max_cost=").unwrap();
+        assert_eq!((None, Some(30), None), (res.min_minutes, res.max_minutes, res.max_cost));
+        assert_eq!(Some(EDifficulty::Medium), res.max_difficulty());
+        assert_eq!("tried=0&max_minutes=30&max_difficulty=medium", res.to_query_string());
+    }
+
+    #[test]
+    fn tried_defaults_for_bookmarks() {
+        let res = SearchPrefill::from_query_string("name=egg").unwrap();
+        assert_eq!(0, res.tried);
+        assert!(!res.has_season(1));
+    }
 }
\ No newline at end of file
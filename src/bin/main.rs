@@ -16,7 +16,7 @@ use recipemanagement::database::establish_connection;
 use recipemanagement::parsetypes::{ESeason, FileWithCourse, ParseRecipe};
 use recipemanagement::parsetypes::ESeason::Independent;
 
-use crate::models::{InsertBook, InsertCourse, InsertIngredient, InsertRecipeIngredient, InsertSeason};
+use crate::models::{InsertBook, InsertCourse, InsertIngredient, InsertRecipeIngredient, InsertSeason, Tag};
 
 fn main() {
     let in_path_file = Path::new("path.txt");
@@ -55,13 +55,20 @@ fn main() {
     let insert_seasons: Vec<InsertSeason> = build_seasons_records();
     let recipe_name_to_id: HashMap<_, _> = recipes.iter().map(|x| (&x.recipe_name, x.recipe_id)).collect();
 
-    let ingredient_infos = build_tag_records(res, recipe_name_to_id);
+    let con = &mut establish_connection();
+    let tag_names = read_tag_names(Path::new("tags.txt"), con);
+    let recipe_tags: Vec<(i32, String)> = res.iter()
+        .flat_map(|x| x.ingredients.iter()
+            .filter(|y| tag_names.contains(&y.to_lowercase()))
+            .map(|y| (recipe_name_to_id.get(&x.name).unwrap().unwrap(), y.to_lowercase())))
+        .unique()
+        .collect();
+    let ingredient_infos = build_tag_records(res, recipe_name_to_id, &tag_names);
 
 /*    let diagnostic_out = tag_stuff.1.iter().map(|x| x.to_string()).join("\n");
     let out_path = Path::new("out_recipe_tags.txt");
     fs::write(out_path, diagnostic_out).unwrap();
 */
-    let con = &mut establish_connection();
 
     use recipemanagement::schema::recipe;
     let recipe_ingredients: Vec<&InsertRecipeIngredient> = ingredient_infos.1.iter().unique().map(|x| x.clone()).collect();
@@ -81,7 +88,8 @@ fn main() {
             .execute(x)
             .unwrap();
         use recipemanagement::schema::season;
-        diesel::insert_into(season::table)
+        // the migrations already create the default seasons
+        diesel::insert_or_ignore_into(season::table)
             .values(&insert_seasons)
             .execute(x)
             .unwrap();
@@ -95,6 +103,25 @@ fn main() {
             .values(recipe_ingredients)
             .execute(x)
             .unwrap();
+        use recipemanagement::schema::tag;
+        for (_, cur_tag) in recipe_tags.iter() {
+            diesel::insert_or_ignore_into(tag::table)
+                .values(tag::tag_name.eq(cur_tag))
+                .execute(x)
+                .unwrap();
+        }
+        let tag_name_to_id: HashMap<String, i32> = tag::table.load::<Tag>(x)
+            .unwrap()
+            .into_iter()
+            .map(|y| (y.tag_name, y.tag_id.unwrap()))
+            .collect();
+        use recipemanagement::schema::recipe_tag;
+        for (cur_recipe_id, cur_tag) in recipe_tags.iter() {
+            diesel::insert_or_ignore_into(recipe_tag::table)
+                .values((recipe_tag::recipe_id.eq(cur_recipe_id), recipe_tag::tag_id.eq(tag_name_to_id[cur_tag])))
+                .execute(x)
+                .unwrap();
+        }
         Ok(())
     });
 
@@ -102,17 +129,30 @@ fn main() {
     print!("{:?}", books)
 }
 
-fn build_tag_records(a: Vec<ParseRecipe>, recipe_name_to_id: HashMap<&String, Option<i32>>) -> (Vec<InsertIngredient>, Vec<InsertRecipeIngredient>) {
+/// Bracketed names listed in `tags_path` or already known as tags are tags, everything else is an ingredient.
+fn read_tag_names(tags_path: &Path, con: &mut SqliteConnection) -> HashSet<String> {
+    use recipemanagement::schema::tag::dsl::*;
+    let known: Vec<String> = tag.select(tag_name).load::<String>(con).unwrap();
+    fs::read_to_string(tags_path).unwrap_or_default()
+        .lines()
+        .map(|x| x.trim().to_lowercase())
+        .filter(|x| !x.is_empty())
+        .chain(known.into_iter().map(|x| x.to_lowercase()))
+        .collect()
+}
+
+fn build_tag_records(a: Vec<ParseRecipe>, recipe_name_to_id: HashMap<&String, Option<i32>>, tag_names: &HashSet<String>) -> (Vec<InsertIngredient>, Vec<InsertRecipeIngredient>) {
     let tags: Vec<InsertIngredient> = a.iter()
         .map(|x| &x.ingredients)
         .flatten()
+        .filter(|x| !tag_names.contains(&x.to_lowercase()))
         .unique()
         .enumerate()
         .map(|(i, x)| InsertIngredient { name: x.to_string(), id: Some(i as i32) })
         .collect();
     let tag_name_to_id: HashMap<_, _> = tags.iter().map(|x| (&x.name, x.id.unwrap()))
         .collect();
-    let recipe_tags = a.iter().map(|x| x.ingredients.iter().map(|y| InsertRecipeIngredient { ingredient_id: *tag_name_to_id.get(y).unwrap(), recipe_id: recipe_name_to_id.get(&x.name).unwrap().unwrap() }))
+    let recipe_tags = a.iter().map(|x| x.ingredients.iter().filter(|y| tag_name_to_id.contains_key(y)).map(|y| InsertRecipeIngredient { ingredient_id: *tag_name_to_id.get(y).unwrap(), recipe_id: recipe_name_to_id.get(&x.name).unwrap().unwrap() }))
         .flatten().collect();
 
 
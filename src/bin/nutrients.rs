@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+use std::{env, fs};
+
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::upsert::excluded;
+
+use recipemanagement::database::establish_connection;
+use recipemanagement::models::{Ingredient, Nutrient};
+use recipemanagement::nutrition::{match_food, parse_nutrient_csv};
+
+/// `nutrients <source> <file.csv>` imports a nutrient table with values per 100 g, like the bundled
+/// `data/nutrients_basic.csv` or exports of USDA FoodData Central and CIQUAL.
+/// Importing the same source again updates its rows, ingredients without nutrients are then mapped by name.
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        eprintln!("usage: nutrients <source> <file.csv>");
+        std::process::exit(2);
+    }
+    let cur_source = args[1].trim().to_lowercase();
+    let content = fs::read_to_string(&args[2]).expect("The nutrient file should be readable");
+    let rows = parse_nutrient_csv(content.as_str()).expect("The nutrient file should have a code and a name column");
+
+    let con = &mut establish_connection();
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::nutrient::dsl::*;
+        for row in rows.iter() {
+            diesel::insert_into(nutrient)
+                .values((source.eq(&cur_source), source_code.eq(&row.source_code), food_name.eq(&row.food_name),
+                         energy_kcal.eq(row.energy_kcal), protein_g.eq(row.protein_g), fat_g.eq(row.fat_g),
+                         carbs_g.eq(row.carbs_g), fibre_g.eq(row.fibre_g)))
+                .on_conflict((source, source_code))
+                .do_update()
+                .set((food_name.eq(excluded(food_name)), energy_kcal.eq(excluded(energy_kcal)), protein_g.eq(excluded(protein_g)),
+                      fat_g.eq(excluded(fat_g)), carbs_g.eq(excluded(carbs_g)), fibre_g.eq(excluded(fibre_g))))
+                .execute(x)?;
+        }
+        Ok(())
+    }).unwrap();
+    println!("imported {} foods from {}", rows.len(), args[2]);
+
+    let foods: Vec<Nutrient> = {
+        use recipemanagement::schema::nutrient::dsl::*;
+        nutrient.filter(source.eq(&cur_source)).order(nutrient_id).load::<Nutrient>(con).unwrap()
+    };
+    let mapped: HashSet<i32> = {
+        use recipemanagement::schema::ingredient_nutrient::dsl::*;
+        ingredient_nutrient.select(ingredient_id).load::<i32>(con).unwrap().into_iter().collect()
+    };
+    let ingredients: Vec<Ingredient> = {
+        use recipemanagement::schema::ingredient::dsl::*;
+        ingredient.load::<Ingredient>(con).unwrap()
+    };
+    let mut count = 0;
+    for cur_ingredient in ingredients.iter().filter(|x| x.id.is_some_and(|y| !mapped.contains(&y))) {
+        let Some(found) = match_food(cur_ingredient.name.as_deref().unwrap_or(""), &foods) else {
+            continue;
+        };
+        use recipemanagement::schema::ingredient_nutrient::dsl::*;
+        diesel::insert_into(ingredient_nutrient)
+            .values((ingredient_id.eq(cur_ingredient.id.unwrap()), nutrient_id.eq(found.nutrient_id.unwrap())))
+            .execute(con)
+            .unwrap();
+        count += 1;
+    }
+    println!("mapped {} ingredients by name", count);
+}
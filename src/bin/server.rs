@@ -8,8 +8,8 @@ use argon2::{
     },
     Argon2,
 };
-use axum::extract::{Path, Query, State};
-use axum::http::StatusCode;
+use axum::extract::{DefaultBodyLimit, Multipart, Path, Query, RawForm, RawQuery, State};
+use axum::http::{header, StatusCode};
 use axum::response::Html;
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::{routing::{get, post}, Form, Router};
@@ -17,10 +17,12 @@ use axum_sessions::async_session::log::trace;
 use axum_sessions::{async_session::CookieStore, extractors::WritableSession, SessionLayer};
 use diesel::dsl::{exists, max};
 use diesel::prelude::*;
+use chrono::{Datelike, Local, NaiveDate};
 use diesel::result::Error;
 use diesel::sql_types::{Integer, Text};
 use diesel::{select, sql_query};
 use env_logger::Env;
+use log::{error, warn};
 use itertools::Itertools;
 use serde::Deserialize;
 use std::collections::hash_map::RandomState;
@@ -29,15 +31,29 @@ use std::net::SocketAddr;
 use std::ops::Deref;
 
 use recipemanagement::args::{RecipePrefill, SearchPrefill};
+use recipemanagement::comments::{comment_and_replies, thread_comments};
+use recipemanagement::cook::find_timers;
+use recipemanagement::cost::{describe_price, is_stale, CurrentPrice, EPriceUnit};
+use recipemanagement::diet::{EDiet, EIngredientAttribute};
+use recipemanagement::markdown::{cook_steps, render_markdown, render_recipe_text, ESection};
+use recipemanagement::nutrition::{estimate_nutrition, food_label, parse_food_label, NutritionInput, Nutrients};
 use recipemanagement::models::*;
-use recipemanagement::parsetypes::ESeason;
-use recipemanagement::queries::{query_all_recipes, RecipeQueryResult};
+use recipemanagement::print::{book_reference, recipes_pdf, EPaperFormat, PrintRecipe};
+use recipemanagement::produce::{rank_by_season, ProduceConfig};
+use recipemanagement::rating::{rating_order, RatingSummary};
+use recipemanagement::recipe_links::{component_ids, creates_cycle, linked_recipes, used_in_ids, ComponentRecipe, ELinkType, LinkedRecipe};
+use recipemanagement::recipe_time::{describe_times, parse_count, times_from_json_ld, EDifficulty, RecipeTimes};
+use recipemanagement::revision::{revision_entries, RecipeSnapshot};
+use recipemanagement::queries::{query_accessible_collections, query_all_recipes, query_diet_overrides, query_display_names, query_equipment, query_in_season_ingredients, query_ingredient_attributes, query_ingredient_nutrients, query_ingredient_prices, query_produce_calendar, query_produce_regions, query_rating_summaries, query_recipe_cost, query_recipe_covers, query_recipe_diets, query_recipe_links, query_recipe_names, query_recipe_seasons, query_recipes_to_equipment_ids, query_recipes_to_ingredient_ids, query_recipe_snapshot, query_season_names, query_seasons, query_recipes_to_diets, query_recipes_to_tags, query_revisions, query_synonym_groups, query_user_equipment_ids, RecipeQueryResult};
 use recipemanagement::schema::course::dsl::course;
+use recipemanagement::search::language::ERecipeLanguage;
 use recipemanagement::search::search_toggle;
+use recipemanagement::seasonality::{month_name, months, seasons_in_month, MonthRange, Season};
 use recipemanagement::secret::get_secret;
-use recipemanagement::strops::extract_domain;
+use recipemanagement::strops::{extract_domain, parse_tags};
 use recipemanagement::templates::*;
-use recipemanagement::text_search::{nuke_and_rebuild_with_recipes, setup_search_state, update_index, SearchState};
+use recipemanagement::upload::{content_type, find_original, generate_thumbnails, is_stored_name, parse_thumbnail_name, remove_image, store_image, upload_dir, MAX_UPLOAD_BYTES};
+use recipemanagement::text_search::{nuke_and_rebuild_with_recipes, reload_synonyms, setup_search_state, update_index, update_index_with_recipes, SearchState};
 use recipemanagement::*;
 
 const SESSION_VERSION: usize = 1;
@@ -60,8 +76,9 @@ async fn main() {
         .write_style_or("MY_LOG_STYLE", "always");
 
     env_logger::init_from_env(env);
-    let search_state = setup_search_state().unwrap();
     let con = &mut database::establish_connection();
+    let synonym_groups = query_synonym_groups(con).into_iter().map(|x| x.1).collect();
+    let search_state = setup_search_state(synonym_groups).unwrap();
     let all_recipes = query_all_recipes(con);
     nuke_and_rebuild_with_recipes(&search_state, all_recipes);
 
@@ -73,10 +90,53 @@ async fn main() {
         .route("/book/add", get(book_form).post(post_book))
         .route("/recipe/add", get(recipe_form).post(post_recipe))
         .route("/search", get(search_form).post(search_result))
+        .route("/search/saved", post(post_saved_search))
+        .route("/search/saved/:id", get(saved_search_view))
+        .route("/search/saved/:id/delete", post(delete_saved_search))
         .route("/login", get(login_page).post(my_login))
         .route("/recipe/edit/:id", get(edit_recipe_form).post(put_recipe))
         .route("/api/tried/:id", post(toggle_tried))
+        .route("/api/recipe_text/preview", post(preview_recipe_text))
         .route("/recipe/detail/:id", get(recipe_detail).post(post_comment))
+        .route("/recipe/revision/:id/restore", post(restore_revision))
+        .route("/recipe/cook/:id", get(cook_mode))
+        .route("/recipe/print/:id", get(print_card))
+        .route("/recipe/pdf/:id", get(recipe_pdf))
+        .route("/course/:name/pdf", get(course_pdf))
+        .route("/collection/:id/pdf", get(collection_pdf))
+        .route("/search/pdf", get(search_pdf))
+        .route("/admin/synonyms", get(synonym_admin).post(post_synonym_group))
+        .route("/admin/tags", get(tag_admin).post(post_reclassify))
+        .route("/admin/ingredients", get(ingredient_admin).post(post_ingredient_attributes))
+        .route("/admin/produce", get(produce_admin).post(post_produce_months))
+        .route("/admin/seasons", get(season_admin).post(post_season))
+        .route("/admin/equipment", get(equipment_admin).post(post_equipment))
+        .route("/admin/prices", get(price_admin).post(post_ingredient_price))
+        .route("/recipe/diet/:id", post(post_diet_override))
+        .route("/recipe/seasons/:id", post(post_recipe_seasons))
+        .route("/recipe/links/:id", post(post_recipe_link))
+        .route("/recipe/quantities/:id", post(post_recipe_quantities))
+        .route("/recipe/equipment/:id", post(post_recipe_equipment))
+        .route("/recipe/rating/:id", post(post_rating))
+        .route("/recipe/cooked/:id", post(post_cooked).layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES)))
+        .route("/cooked/:id/delete", post(delete_cooked))
+        .route("/uploads/:name", get(serve_upload))
+        .route("/recipe/images/:id", post(post_recipe_images).layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES)))
+        .route("/recipe/image/:id/cover", post(set_cover_image))
+        .route("/recipe/image/:id/delete", post(delete_recipe_image))
+        .route("/comment/:id/edit", post(edit_comment))
+        .route("/comment/:id/delete", post(delete_comment))
+        .route("/history", get(cooking_history))
+        .route("/settings", get(settings_page).post(post_settings))
+        .route("/settings/equipment", post(post_settings_equipment))
+        .route("/top", get(top_rated))
+        .route("/in_season", get(in_season_now))
+        .route("/collections", get(collection_list).post(post_collection))
+        .route("/collection/:id", get(collection_detail))
+        .route("/collection/:id/entry/:recipe_id", post(post_collection_entry))
+        .route("/collection/:id/share", post(post_collection_share))
+        .route("/collection/:id/delete", post(delete_collection))
+        .route("/api/collection/:id/recipe/:recipe_id", post(add_to_collection))
         .layer(session_layer)
         .with_state(search_state)
         ;
@@ -107,6 +167,24 @@ fn nuke_and_rebuild_index(search_state: &SearchState) {
     nuke_and_rebuild_with_recipes(search_state, all_recipes);
 }
 
+/// Recipes using `cur_recipe_id` as a sub-recipe are indexed with its ingredients, so they are updated as well.
+fn update_index_with_users(search_state: &SearchState, con: &mut SqliteConnection, cur_recipe_id: i32) {
+    let mut affected = used_in_ids(cur_recipe_id, &query_recipe_links(con));
+    affected.push(cur_recipe_id);
+    update_index_for(search_state, con, &affected);
+}
+
+/// Reindexes only the given recipes, for changes that cannot affect any other.
+fn update_index_for(search_state: &SearchState, con: &mut SqliteConnection, recipe_ids: &[i32]) {
+    if recipe_ids.is_empty() {
+        return;
+    }
+    let affected: Vec<RecipeQueryResult> = query_all_recipes(con).into_iter()
+        .filter(|x| x.recipe.recipe_id.is_some_and(|y| recipe_ids.contains(&y)))
+        .collect();
+    update_index_with_recipes(search_state, affected);
+}
+
 
 async fn index_handler(session: WritableSession) -> Html<String> {
     let con = &mut database::establish_connection();
@@ -115,12 +193,15 @@ async fn index_handler(session: WritableSession) -> Html<String> {
     let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
     let course_refs: &Vec<QCourse> = &courses;
     let build_version = env!("VERGEN_GIT_SHA");
+    let is_admin = maybe_user_id.map(|x| user_is_admin(x, con)).unwrap_or(false);
 
     let hello = HelloTemplate {
         name: "world",
         courses: course_refs,
+        saved_searches: query_saved_searches(maybe_user_id, con),
         title: "Recipes",
         user_id: maybe_user_id,
+        is_admin,
         build_version,
         debug_compilation: cfg!(debug_assertions),
     }; // instantiate your struct
@@ -197,13 +278,19 @@ async fn handle_course(session: WritableSession, Path(path): Path<String>) -> Ht
     let web_pages: HashSet<String> = HashSet::from_iter(external_web_recipes.iter()
         .filter(|x| x.recipe_url.as_ref().filter(|y| !y.trim().is_empty()).is_some())
         .map(|x| extract_domain(x.recipe_url.clone().unwrap())));
-    let mut recipes_by_season_and_source: Vec<(ESeason, Vec<(String, Vec<FullRecipe>)>)> = vec![];
-    for season in ESeason::get_seasons() {
+    // a recipe is listed under each of its seasons
+    let recipe_seasons = query_recipe_seasons(con);
+    let has_season = |x: &FullRecipe, season: &Season| recipe_seasons.get(&x.recipe_id.unwrap())
+        .map(|y| y.iter().any(|z| z.season_id == season.season_id))
+        .unwrap_or(x.primary_season == season.season_id);
+    let seasons = query_seasons(con);
+    let mut recipes_by_season_and_source: Vec<(Season, Vec<(String, Vec<FullRecipe>)>)> = vec![];
+    for season in seasons.iter() {
         let mut vals: Vec<(String, Vec<FullRecipe>)> = vec![];
         for lol_book in books.clone() {
             let temp: Vec<FullRecipe> = recipes.iter()
                 .filter(|x| x.book_id.filter(|y| *y == lol_book.book_id.unwrap()).is_some())
-                .filter(|x| x.primary_season == season.value_rofl() as i32)
+                .filter(|x| has_season(x, season))
                 .map(|x| x.clone())
                 .collect();
             if !temp.is_empty() {
@@ -215,7 +302,7 @@ async fn handle_course(session: WritableSession, Path(path): Path<String>) -> Ht
             let temp: Vec<FullRecipe> = recipes.iter()
                 .filter(|x| x.book_id.is_none())
                 .filter(|x| x.recipe_url.as_ref().filter(|y| y.contains(web_page.as_str())).is_some())
-                .filter(|x| x.primary_season == season.value_rofl() as i32)
+                .filter(|x| has_season(x, season))
                 .map(|x| x.clone())
                 .collect();
             if !temp.is_empty() {
@@ -227,22 +314,23 @@ async fn handle_course(session: WritableSession, Path(path): Path<String>) -> Ht
         let here_temp: Vec<FullRecipe> = recipes.iter()
             .filter(|x| x.book_id.is_none())
             .filter(|x| x.recipe_url.as_ref().filter(|x| !x.trim().is_empty()).is_none())
-            .filter(|x| x.primary_season == season.value_rofl() as i32)
+            .filter(|x| has_season(x, season))
             .map(|x| x.clone())
             .collect();
         if !here_temp.is_empty() {
             vals.push(("Here".to_string(), here_temp))
         }
 
-        recipes_by_season_and_source.push((season, vals));
+        recipes_by_season_and_source.push((season.clone(), vals));
     }
     let build_version = env!("VERGEN_GIT_SHA");
 
     let das_content = CourseTemplate {
         course_name: asdf.course_name.as_ref().unwrap().as_str(),
-        seasons: ESeason::get_seasons(),
+        seasons,
         books: &books,
         courses: course_refs,
+        saved_searches: query_saved_searches(maybe_user_id, con),
         title: cur_name,
         tried: tried_ids,
         logged_in: maybe_user_id.is_some(),
@@ -252,6 +340,11 @@ async fn handle_course(session: WritableSession, Path(path): Path<String>) -> Ht
         build_version,
         commented,
         texted,
+        collections: query_own_collections(maybe_user_id, con),
+        recipes_to_tags: query_recipes_to_tags(con),
+        recipes_to_diets: query_recipes_to_diets(con),
+        ratings: query_rating_summaries(con),
+        covers: query_recipe_covers(con),
         debug_compilation: cfg!(debug_assertions),
     }.get();
 
@@ -280,9 +373,12 @@ async fn recipe_form(session: WritableSession, prefill: Query<RecipePrefill>) ->
 
 
     Html(RecipeForm {
-        seasons: ESeason::get_seasons(),
+        seasons: query_seasons(con),
+        languages: ERecipeLanguage::get_languages(),
+        difficulties: EDifficulty::get_difficulties(),
         books: &books,
         courses: course_refs,
+        saved_searches: query_saved_searches(maybe_user_id, con),
         prefill: prefill.0,
         title: "Add Recipe",
         newest: newest_recipe,
@@ -305,7 +401,14 @@ struct PostRecipe {
     recipe_url: Option<String>,
     recipe_text: Option<String>,
     ingredients: Option<String>,
-
+    tags: Option<String>,
+    language: Option<String>,
+    servings: Option<String>,
+    prep_minutes: Option<String>,
+    cook_minutes: Option<String>,
+    total_minutes: Option<String>,
+    difficulty: Option<String>,
+    json_ld: Option<String>,
 }
 
 async fn post_recipe(State(search_state): State<SearchState>, session: WritableSession, Form(form): Form<PostRecipe>) -> Response {
@@ -320,7 +423,11 @@ async fn post_recipe(State(search_state): State<SearchState>, session: WritableS
     let page = form.page.map(|x| x.parse::<i32>()).and_then(|x| x.ok());
     let recipe_url = form.recipe_url.map(|x| x.trim().to_string()).filter(|x| !x.is_empty());
     let mut cur_recipe_id: i32 = 0;
-    let recipe_struct = InsertRecipeWithUrl { recipe_id: None, recipe_name: form.name, primary_season: form.season, course_id: form.course, book_id, page, recipe_url };
+    let language = form.language.as_deref().and_then(ERecipeLanguage::from_code).map(|x| x.code().to_string());
+    let times = form_times(form.servings.as_deref(), form.prep_minutes.as_deref(), form.cook_minutes.as_deref(), form.total_minutes.as_deref(), form.json_ld.as_deref());
+    let difficulty = form.difficulty.as_deref().and_then(EDifficulty::from_code).map(|x| x.code().to_string());
+    let recipe_struct = InsertRecipeWithUrl { recipe_id: None, recipe_name: form.name, primary_season: form.season, course_id: form.course, book_id, page, recipe_url, language,
+        servings: times.servings, prep_minutes: times.prep_minutes, cook_minutes: times.cook_minutes, total_minutes: times.total_minutes, difficulty };
     con.transaction::<_, Error, _>(|x| {
         diesel::insert_into(schema::recipe::table)
             .values(vec![recipe_struct])
@@ -360,6 +467,8 @@ async fn post_recipe(State(search_state): State<SearchState>, session: WritableS
                 .execute(x)
                 .unwrap();
         }
+        save_recipe_tags(x, cur_recipe_id, &parse_tags(form.tags.as_deref().unwrap_or("")))?;
+        record_revision(x, cur_recipe_id, maybe_user_id.unwrap(), None)?;
         return Ok(());
     }
     ).unwrap();
@@ -380,6 +489,7 @@ async fn post_recipe(State(search_state): State<SearchState>, session: WritableS
 #[derive(Deserialize)]
 struct PostBook {
     booktext: String,
+    language: Option<String>,
 }
 
 async fn book_form(session: WritableSession) -> Response {
@@ -396,7 +506,9 @@ async fn book_form(session: WritableSession) -> Response {
 
     Html(BookForm {
         courses: course_refs,
+        saved_searches: query_saved_searches(maybe_user_id, con),
         title: "Add book",
+        languages: ERecipeLanguage::get_languages(),
         user_id: maybe_user_id,
         build_version,
         debug_compilation: cfg!(debug_assertions),
@@ -419,10 +531,11 @@ async fn post_book(session: WritableSession, Form(form): Form<PostBook>) -> Redi
         }
     }
     use recipemanagement::schema::book;
-    use crate::schema::book::book_name;
+    use crate::schema::book::{book_name, language};
+    let book_language = content.language.as_deref().and_then(ERecipeLanguage::from_code).map(|x| x.code().to_string());
 
     diesel::insert_into(book::table)
-        .values(book_name.eq(content.booktext))
+        .values((book_name.eq(content.booktext), language.eq(book_language)))
         .execute(con)
         .unwrap();
 
@@ -431,50 +544,17 @@ async fn post_book(session: WritableSession, Form(form): Form<PostBook>) -> Redi
 }
 
 
-async fn search_form(session: WritableSession) -> Response {
+async fn search_form(State(search_state): State<SearchState>, session: WritableSession, RawQuery(raw_query): RawQuery) -> Response {
     let maybe_user_id = get_user_id(session);
     if maybe_user_id.is_none() {
         return Redirect::to("/login").into_response();
     }
+    let con = &mut database::establish_connection();
+    let prefill = raw_query.as_deref()
+        .filter(|x| !x.is_empty())
+        .and_then(SearchPrefill::from_query_string);
 
-    let con: &mut SqliteConnection = &mut database::establish_connection();
-
-    use recipemanagement::schema::book::dsl::*;
-
-    let books: Vec<QBook> = book.load::<QBook>(con).unwrap().into_iter().sorted_by(|x, y| x.book_name.as_ref().unwrap().cmp(y.book_name.as_ref().unwrap())).collect();
-
-    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
-    let course_refs: &Vec<QCourse> = &courses;
-    let id_to_book_name = books.iter()
-        .map(|x| (x.book_id.clone().unwrap(), x.book_name.clone().unwrap()))
-        .collect();
-
-    let _build_version = env!("VERGEN_GIT_SHA");
-    use recipemanagement::schema::recipe_text::dsl::*;
-    let texted: HashSet<i32> = recipe_text.load::<RecipeText>(con).unwrap().iter()
-        .filter(|x| !x.content.is_empty())
-        .map(|x| x.recipe_id).collect();
-    use recipemanagement::schema::recipe_comment::dsl::*;
-    let commented: HashSet<i32> = recipe_comment.load::<Comment>(con).unwrap().iter().map(|x| x.recipe_id).collect();
-    let tried_ids: HashSet<i32> = query_tried(maybe_user_id.unwrap(), con);
-
-
-    Html(SearchForm {
-        seasons: ESeason::get_seasons(),
-        books: &books,
-        courses: course_refs,
-        recipes: None,
-        title: "Search",
-        recipes_to_ingredients: Default::default(),
-        user_id: maybe_user_id,
-        build_version: "build_version",
-        prefill: SearchPrefill::default(),
-        id_to_book_name,
-        commented,
-        texted,
-        tried_ids,
-        debug_compilation: cfg!(debug_assertions),
-    }.get()).into_response()
+    render_search(&search_state, con, maybe_user_id.unwrap(), prefill, None)
 }
 
 fn query_tried(query_user_id: i32, con: &mut SqliteConnection) -> HashSet<i32> {
@@ -486,30 +566,36 @@ fn query_tried(query_user_id: i32, con: &mut SqliteConnection) -> HashSet<i32> {
 
 }
 
-async fn search_result(State(search_state): State<SearchState>, session: WritableSession, Form(form): Form<SearchPrefill>) -> Response {
+fn user_is_admin(query_user_id: i32, con: &mut SqliteConnection) -> bool {
+    use recipemanagement::schema::user::dsl::*;
+    user.filter(id.eq(query_user_id))
+        .select(is_admin)
+        .first::<bool>(con)
+        .unwrap_or(false)
+}
 
+/// The season checkboxes are not plain fields, so the form goes through `from_query_string` like a bookmarked search.
+async fn search_result(State(search_state): State<SearchState>, session: WritableSession, RawForm(form): RawForm) -> Response {
     let maybe_user_id = get_user_id(session);
     if maybe_user_id.is_none() {
         return Redirect::to("/login").into_response();
     }
-
+    let Some(prefill) = std::str::from_utf8(&form).ok().and_then(SearchPrefill::from_query_string) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
     let con = &mut database::establish_connection();
 
+    render_search(&search_state, con, maybe_user_id.unwrap(), Some(prefill), None)
+}
 
-
+/// Without a prefill only the empty form is shown, smart collections pass the saved search they were loaded from.
+fn render_search(search_state: &SearchState, con: &mut SqliteConnection, query_user_id: i32, prefill: Option<SearchPrefill>, collection: Option<SavedSearch>) -> Response {
     use recipemanagement::schema::book::dsl::*;
-
-    let _books: Vec<QBook> = book.load::<QBook>(con).unwrap();
+    let books: Vec<QBook> = book.load::<QBook>(con).unwrap().into_iter().sorted_by(|x, y| x.book_name.as_ref().unwrap().cmp(y.book_name.as_ref().unwrap())).collect();
     use recipemanagement::schema::course::dsl::*;
-
     let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
-    let course_refs: &Vec<QCourse> = &courses;
 
-
-    
-
-    let books: Vec<QBook> = book.load::<QBook>(con).unwrap();
-    let recipes = search_toggle::search(&form, con, &search_state.index, maybe_user_id.unwrap());
+    let search_outcome = prefill.as_ref().map(|x| search_toggle::search(x, con, &search_state.index, query_user_id));
 
     use recipemanagement::schema::ingredient::dsl::*;
     let id_to_ingredients: HashMap<i32, String> = ingredient.load::<Ingredient>(con)
@@ -528,8 +614,8 @@ async fn search_result(State(search_state): State<SearchState>, session: Writabl
         .into_group_map();
 
     let build_version = env!("VERGEN_GIT_SHA");
-        let id_to_book_name = books.iter()
-        .map(|x| (x.book_id.clone().unwrap(), x.book_name.clone().unwrap()))
+    let id_to_book_name = books.iter()
+        .map(|x| (x.book_id.unwrap(), x.book_name.clone().unwrap()))
         .collect();
     use recipemanagement::schema::recipe_text::dsl::*;
     let texted: HashSet<i32> = recipe_text.load::<RecipeText>(con).unwrap().iter()
@@ -537,27 +623,139 @@ async fn search_result(State(search_state): State<SearchState>, session: Writabl
         .map(|x| x.recipe_id).collect();
     use recipemanagement::schema::recipe_comment::dsl::*;
     let commented: HashSet<i32> = recipe_comment.load::<Comment>(con).unwrap().iter().map(|x| x.recipe_id).collect();
-    let tried_ids: HashSet<i32> = query_tried(maybe_user_id.expect("user should be logged in alrady"), con);
-
+    let tried_ids: HashSet<i32> = query_tried(query_user_id, con);
+    let page_title = collection.as_ref().map(|x| x.search_name.clone()).unwrap_or("Search".to_string());
+    let (recipes, facets, suggestion) = match search_outcome {
+        Some(outcome) => (Some(outcome.recipes), outcome.facets, outcome.suggestion),
+        None => (None, None, None),
+    };
+    let result_ids: HashSet<i32> = recipes.iter().flatten().filter_map(|x| x.recipe_id).collect();
+    let recipe_names = query_recipe_names(con);
+    let accompaniments: Vec<(i32, String)> = recipe_links::accompaniments(&result_ids, &query_recipe_links(con)).into_iter()
+        .take(10)
+        .filter_map(|x| Some((x, recipe_names.get(&x)?.clone())))
+        .collect();
 
     Html(SearchForm {
-        seasons: ESeason::get_seasons(),
+        seasons: query_seasons(con),
         books: &books,
-        courses: course_refs,
-        recipes: Some(recipes),
-        title: "Search",
+        courses: &courses,
+        saved_searches: query_saved_searches(Some(query_user_id), con),
+        recipes,
+        title: page_title.as_str(),
         recipes_to_ingredients,
-        user_id: maybe_user_id,
+        user_id: Some(query_user_id),
         build_version,
-        prefill: form,
+        prefill: prefill.unwrap_or_default(),
         id_to_book_name,
         commented,
         texted,
         tried_ids,
+        facets,
+        suggestion,
+        collection,
+        collections: query_accessible_collections(con, query_user_id),
+        tags: query_recipes_to_tags(con).into_values().flatten().unique().sorted().collect(),
+        diets: EDiet::get_diets(),
+        difficulties: EDifficulty::get_difficulties(),
+        recipes_to_diets: query_recipes_to_diets(con),
+        ratings: query_rating_summaries(con),
+        covers: query_recipe_covers(con),
+        accompaniments,
         debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+fn query_saved_searches(query_user_id: Option<i32>, con: &mut SqliteConnection) -> Vec<SavedSearch> {
+    let Some(cur_user_id) = query_user_id else { return vec![] };
+    use recipemanagement::schema::saved_search::dsl::*;
+    saved_search.filter(user_id.eq(cur_user_id))
+        .order(search_name.asc())
+        .load::<SavedSearch>(con)
+        .unwrap()
+}
+
+#[derive(Deserialize)]
+struct PostSavedSearch {
+    search_name: String,
+    query: String,
+    smart_collection: Option<String>,
+}
+
+async fn post_saved_search(session: WritableSession, Form(form): Form<PostSavedSearch>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    // only keep the parameters the search understands
+    let cur_query = SearchPrefill::from_query_string(&form.query).unwrap_or_default().to_query_string();
+    let cur_name = form.search_name.trim().to_string();
+    if cur_name.is_empty() {
+        return Redirect::to(format!("/search?{}", cur_query).as_str()).into_response();
+    }
+    let con = &mut database::establish_connection();
+    use recipemanagement::schema::saved_search::dsl::*;
+    let insert_saved_search = InsertSavedSearch {
+        user_id: maybe_user_id.unwrap(),
+        search_name: cur_name.clone(),
+        query: cur_query.clone(),
+        smart_collection: form.smart_collection.is_some(),
+    };
+    // saving under an existing name updates it in place, so bookmarks of /search/saved/:id keep working
+    diesel::insert_into(saved_search)
+        .values(&insert_saved_search)
+        .on_conflict((user_id, search_name))
+        .do_update()
+        .set((query.eq(cur_query), smart_collection.eq(insert_saved_search.smart_collection)))
+        .execute(con)
+        .unwrap();
+    let cur_id = saved_search.filter(user_id.eq(maybe_user_id.unwrap()))
+        .filter(search_name.eq(cur_name))
+        .select(saved_search_id)
+        .first::<Option<i32>>(con)
+        .unwrap()
+        .unwrap();
+
+    Redirect::to(format!("/search/saved/{}", cur_id).as_str()).into_response()
+}
+
+async fn saved_search_view(State(search_state): State<SearchState>, session: WritableSession, Path(path): Path<i32>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let found = {
+        use recipemanagement::schema::saved_search::dsl::*;
+        saved_search.filter(saved_search_id.eq(path))
+            .filter(user_id.eq(maybe_user_id.unwrap()))
+            .first::<SavedSearch>(con)
+            .optional()
+            .unwrap()
+    };
+    let Some(found) = found else {
+        return Redirect::to("/search").into_response();
+    };
+    if !found.smart_collection {
+        return Redirect::to(format!("/search?{}", found.query).as_str()).into_response();
+    }
+    let prefill = SearchPrefill::from_query_string(&found.query).unwrap_or_default();
+
+    render_search(&search_state, con, maybe_user_id.unwrap(), Some(prefill), Some(found))
+}
 
+async fn delete_saved_search(session: WritableSession, Path(path): Path<i32>) -> Redirect {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login");
     }
-        .get()).into_response()
+    let con = &mut database::establish_connection();
+    use recipemanagement::schema::saved_search::dsl::*;
+    diesel::delete(saved_search.filter(saved_search_id.eq(path)).filter(user_id.eq(maybe_user_id.unwrap())))
+        .execute(con)
+        .unwrap();
+
+    Redirect::to("/search")
 }
 
 async fn login_page(session: WritableSession) -> Html<String> {
@@ -569,6 +767,7 @@ async fn login_page(session: WritableSession) -> Html<String> {
     let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
     Html(LoginPage {
         courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
         title: "Login",
         user_id: maybe_user_id,
         build_version,
@@ -641,7 +840,7 @@ WHERE recipe_id={}", path);
         .load::<Ingredient>(con)
         .unwrap();
     let ingredient_prefill = ingredients.iter().map(|x| x.name.as_ref().unwrap()).join("\n");
-    let prefill_season = das_recipe.as_ref().unwrap().primary_season as usize;
+    let prefill_season = das_recipe.as_ref().unwrap().primary_season;
     use recipemanagement::schema::recipe_text::dsl::*;
     let recipe_text_disp = recipe_text.filter(schema::recipe_text::recipe_id.eq(path))
         .load::<RecipeText>(con)
@@ -651,13 +850,17 @@ WHERE recipe_id={}", path);
 
     Html(RecipeEditForm {
         courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
         recipe: das_recipe.as_ref().unwrap(),
         ingredients: ingredient_prefill,
         title: "Edit recipe",
         books: &books,
-        seasons: ESeason::get_seasons(),
+        seasons: query_seasons(con),
         prefill_season,
+        languages: ERecipeLanguage::get_languages(),
+        difficulties: EDifficulty::get_difficulties(),
         recipe_text: recipe_text_disp,
+        tags: query_recipes_to_tags(con).remove(&path).unwrap_or_default().join(", "),
         user_id: maybe_user_id,
         build_version,
         debug_compilation: cfg!(debug_assertions),
@@ -674,145 +877,407 @@ struct PutRecipe {
     ingredients: Option<String>,
     page: Option<String>,
     recipe_url: Option<String>,
-    recipe_text: Option<String>
+    recipe_text: Option<String>,
+    tags: Option<String>,
+    language: Option<String>,
+    servings: Option<String>,
+    prep_minutes: Option<String>,
+    cook_minutes: Option<String>,
+    total_minutes: Option<String>,
+    difficulty: Option<String>,
+    json_ld: Option<String>,
 }
 
-async fn put_recipe(State(search_state): State<SearchState>, session: WritableSession, Path(path): Path<i32>, Form(form): Form<PutRecipe>) -> Redirect {
-    let maybe_user_id = get_user_id(session);
-    if maybe_user_id.is_none() {
-        return Redirect::to("/login");
-    }
-
-    let con = &mut database::establish_connection();
+/// Numbers typed into the form win over the ones in pasted JSON-LD, servings of zero count as unknown.
+fn form_times(servings: Option<&str>, prep_minutes: Option<&str>, cook_minutes: Option<&str>, total_minutes: Option<&str>, json_ld: Option<&str>) -> RecipeTimes {
+    let typed = RecipeTimes {
+        prep_minutes: parse_count(prep_minutes),
+        cook_minutes: parse_count(cook_minutes),
+        total_minutes: parse_count(total_minutes),
+        servings: parse_count(servings).filter(|x| *x > 0),
+    };
+    typed.or(json_ld.and_then(times_from_json_ld).unwrap_or_default())
+}
 
+/// Shared by editing and restoring a revision. Requests without a servings field keep the stored servings.
+/// An update rather than a replace, which would delete the rows that cascade from the recipe.
+fn save_recipe(x: &mut SqliteConnection, path: i32, form: PutRecipe) -> Result<(), Error> {
     let page_res = form.page.map(|x| x.parse::<i32>()).and_then(|x| x.ok());
 
+    use recipemanagement::schema::recipe::dsl::*;
 
-    let _transaction_res = con.transaction::<_, Error, _>(|x| {
-        use recipemanagement::schema::recipe::dsl::*;
+    let old_recipe_query = recipe.filter(recipe_id.eq(path))
+        .load::<FullRecipe>(x)
+        .unwrap();
+    let old_recipe = old_recipe_query.first().unwrap();
+    let update_url = form.recipe_url.filter(|x| !x.trim().is_empty());
+    let times = form_times(form.servings.as_deref(), form.prep_minutes.as_deref(), form.cook_minutes.as_deref(), form.total_minutes.as_deref(), form.json_ld.as_deref());
+
+
+    diesel::update(recipe.filter(recipe_id.eq(path)))
+        .set((recipe_url.eq(update_url),
+              recipe_name.eq(Some(form.name)),
+              primary_season.eq(form.season),
+              course_id.eq(form.course),
+              page.eq(page_res),
+              book_id.eq(form.book.filter(|x| *x >= 0)),
+              language.eq(form.language.as_deref().and_then(ERecipeLanguage::from_code).map(|x| x.code().to_string())),
+              servings.eq(times.servings.or(old_recipe.servings.filter(|_| form.servings.is_none()))),
+              prep_minutes.eq(times.prep_minutes),
+              cook_minutes.eq(times.cook_minutes),
+              total_minutes.eq(times.total_minutes),
+              difficulty.eq(form.difficulty.as_deref().and_then(EDifficulty::from_code).map(|x| x.code().to_string())),
+              modified_at.eq(Local::now().format("%Y-%m-%d %H:%M:%S").to_string())))
+        .execute(x)?;
+    if form.ingredients.is_some() {
+        use recipemanagement::schema::ingredient::dsl::*;
+        let ingredient_names: Vec<String> = form.ingredients.unwrap().trim()
+            .split("\n")
+            .map(|x| x.trim())
+            .filter(|x| !x.is_empty())
+            .map(|x| x.to_string())
+            .map(|x| x.to_lowercase())
+            .unique()
+            .collect();
 
-        let old_recipe_query = recipe.filter(recipe_id.eq(path))
-            .load::<FullRecipe>(x)
+
+        let existing_ingredients = ingredient
+            .load::<Ingredient>(x)
             .unwrap();
-        let old_recipe = old_recipe_query.first().unwrap();
-        let update_url = form.recipe_url.filter(|x| !x.trim().is_empty());
-
-
-        let edit_recipe = FullInsertRecipe {
-            recipe_id: Some(path),
-            recipe_url: update_url,
-            recipe_name: Some(form.name),
-            primary_season: form.season,
-            course_id: form.course,
-            created_at: old_recipe.created_at,
-            page: page_res,
-            book_id: form.book.filter(|x| *x >= 0),
-        };
 
-        diesel::replace_into(recipe)
-            .values(&vec![edit_recipe])
+        let ingredient_to_id: HashMap<String, i32> = existing_ingredients.iter().map(|x| (x.name.as_ref().clone().unwrap().to_string(), *x.id.as_ref().unwrap()))
+            .collect();
+        let id_to_ingredient: HashMap<i32, String> = existing_ingredients.iter().map(|x| (*x.id.as_ref().unwrap(), x.name.as_ref().unwrap().to_string()))
+            .collect();
+        let existing_names: HashSet<String, RandomState> = HashSet::from_iter(existing_ingredients.iter().map(|x| x.name.as_ref().unwrap().to_string()));
+        let update_names = HashSet::from_iter(ingredient_names.iter().map(|x| x.to_string()));
+
+        sql_query("INSERT INTO recipe_text(recipe_id, content) VALUES (?, ?)
+ON CONFLICT(recipe_id) DO UPDATE SET content = excluded.content, modified_at = datetime('now','localtime')
+WHERE content != excluded.content;")
+            .bind::<Integer, _>(path)
+            .bind::<Text, _>(form.recipe_text.unwrap_or("".to_string()))
+            .execute(x)?;
+
+
+
+        use recipemanagement::schema::recipe_ingredient::dsl::*;
+
+        let assigned_ingredients = recipe_ingredient.filter(schema::recipe_ingredient::recipe_id.eq(path))
+            .load::<RecipeIngredient>(x)
+            .unwrap();
+
+        let assigned_names: HashSet<String, RandomState> = HashSet::from_iter(assigned_ingredients.iter().map(|x| id_to_ingredient.get(&x.ingredient_id).unwrap().to_string()));
+
+        let to_connect = update_names.intersection(&existing_names)
+            .into_iter()
+            .map(|x| x.to_string())
+            .collect::<HashSet<String, RandomState>>()
+            .difference(&assigned_names)
+            .map(|x| x.to_string())
+            .collect::<HashSet<String, RandomState>>();
+        let to_delete: HashSet<String> = assigned_names.difference(&update_names).into_iter().map(|x| x.clone()).collect();
+        let to_insert: HashSet<String> = update_names.difference(&existing_names).into_iter().map(|x| x.clone()).collect();
+
+        let connect_obs: Vec<InsertRecipeIngredient> = to_connect.into_iter()
+            .map(|x| ingredient_to_id.get(x.as_str()).unwrap())
+            .map(|x| InsertRecipeIngredient { recipe_id: path, ingredient_id: *x })
+            .collect();
+        diesel::insert_into(recipe_ingredient)
+            .values(connect_obs)
             .execute(x)
             .unwrap();
-        if form.ingredients.is_some() {
-            use recipemanagement::schema::ingredient::dsl::*;
-            let ingredient_names: Vec<String> = form.ingredients.unwrap().trim()
-                .split("\n")
-                .map(|x| x.trim())
-                .map(|x| x.to_string())
-                .map(|x| x.to_lowercase())
-                .unique()
-                .collect();
 
 
-            let existing_ingredients = ingredient
-                .load::<Ingredient>(x)
-                .unwrap();
+        let delete_ids: Vec<i32> = to_delete.into_iter().map(|x| *(ingredient_to_id.get(x.as_str()).unwrap()))
+            .collect();
+        diesel::delete(recipe_ingredient.filter(schema::recipe_ingredient::recipe_id.eq(path)).filter(ingredient_id.eq_any(&delete_ids)))
+            .execute(x).unwrap();
 
-            let ingredient_to_id: HashMap<String, i32> = existing_ingredients.iter().map(|x| (x.name.as_ref().clone().unwrap().to_string(), *x.id.as_ref().unwrap()))
-                .collect();
-            let id_to_ingredient: HashMap<i32, String> = existing_ingredients.iter().map(|x| (*x.id.as_ref().unwrap(), x.name.as_ref().unwrap().to_string()))
-                .collect();
-            let existing_names: HashSet<String, RandomState> = HashSet::from_iter(existing_ingredients.iter().map(|x| x.name.as_ref().unwrap().to_string()));
-            let update_names = HashSet::from_iter(ingredient_names.iter().map(|x| x.to_string()));
+        let start_id: i32 = ingredient.select(max(id))
+            .first::<Option<i32>>(x)
+            .unwrap()
+            .map(|x| x + 1)
+            .unwrap_or(1);
 
-            use recipemanagement::schema::recipe_text::dsl::*;
-            let edit_recipe_text = InsertRecipeText { recipe_id: path, content: form.recipe_text.unwrap_or("".to_string()) };
-            diesel::replace_into(recipe_text)
-                .values(vec![edit_recipe_text])
-                .execute(x)
-                .unwrap();
 
+        let new_ingredients: Vec<InsertIngredient> = to_insert.iter().enumerate()
+            .map(|(i, x)| InsertIngredient { id: Some(i as i32 + start_id), name: x.to_string() })
+            .collect();
+        let new_refs: Vec<InsertRecipeIngredient> = to_insert.iter()
+            .enumerate()
+            .map(|(i, _x)| InsertRecipeIngredient { recipe_id: path, ingredient_id: i as i32 + start_id })
+            .collect();
 
+        diesel::insert_into(ingredient)
+            .values(new_ingredients)
+            .execute(x)
+            .unwrap();
+        diesel::insert_into(recipe_ingredient)
+            .values(new_refs)
+            .execute(x)
+            .unwrap();
+    }
+    if let Some(tag_string) = form.tags.as_ref() {
+        save_recipe_tags(x, path, &parse_tags(tag_string))?;
+    }
 
-            use recipemanagement::schema::recipe_ingredient::dsl::*;
 
-            let assigned_ingredients = recipe_ingredient.filter(schema::recipe_ingredient::recipe_id.eq(path))
-                .load::<RecipeIngredient>(x)
-                .unwrap();
+    Ok(())
+}
 
-            let assigned_names: HashSet<String, RandomState> = HashSet::from_iter(assigned_ingredients.iter().map(|x| id_to_ingredient.get(&x.ingredient_id).unwrap().to_string()));
-
-            let to_connect = update_names.intersection(&existing_names)
-                .into_iter()
-                .map(|x| x.to_string())
-                .collect::<HashSet<String, RandomState>>()
-                .difference(&assigned_names)
-                .map(|x| x.to_string())
-                .collect::<HashSet<String, RandomState>>();
-            let to_delete: HashSet<String> = assigned_names.difference(&update_names).into_iter().map(|x| x.clone()).collect();
-            let to_insert: HashSet<String> = update_names.difference(&existing_names).into_iter().map(|x| x.clone()).collect();
-
-            let connect_obs: Vec<InsertRecipeIngredient> = to_connect.into_iter()
-                .map(|x| ingredient_to_id.get(x.as_str()).unwrap())
-                .map(|x| InsertRecipeIngredient { recipe_id: path, ingredient_id: *x })
-                .collect();
-            diesel::insert_into(recipe_ingredient)
-                .values(connect_obs)
-                .execute(x)
-                .unwrap();
+/// Adds the state after an edit to the history, a state changed elsewhere (or before the history existed) is kept first without an author.
+fn record_revision(con: &mut SqliteConnection, cur_recipe_id: i32, cur_user_id: i32, before: Option<RecipeSnapshot>) -> Result<(), Error> {
+    let Some(after) = query_recipe_snapshot(con, cur_recipe_id) else { return Ok(()) };
+    if before.as_ref() == Some(&after) {
+        return Ok(());
+    }
+    let last = query_revisions(con, cur_recipe_id).last().map(RecipeSnapshot::from);
+    if let Some(before) = before.filter(|x| last.as_ref() != Some(x)) {
+        insert_revision(con, cur_recipe_id, None, &before)?;
+    }
+    insert_revision(con, cur_recipe_id, Some(cur_user_id), &after)
+}
 
+fn insert_revision(con: &mut SqliteConnection, cur_recipe_id: i32, cur_user_id: Option<i32>, snapshot: &RecipeSnapshot) -> Result<(), Error> {
+    use recipemanagement::schema::recipe_revision::dsl::*;
+    diesel::insert_into(recipe_revision)
+        .values((recipe_id.eq(cur_recipe_id), user_id.eq(cur_user_id), recipe_name.eq(&snapshot.recipe_name),
+                 primary_season.eq(snapshot.primary_season), course_id.eq(snapshot.course_id), book_id.eq(snapshot.book_id),
+                 page.eq(snapshot.page), recipe_url.eq(&snapshot.recipe_url), language.eq(&snapshot.language),
+                 ingredients.eq(snapshot.ingredients.join("\n")), tags.eq(snapshot.tags.join("\n")), content.eq(&snapshot.content),
+                 servings.eq(snapshot.servings), prep_minutes.eq(snapshot.prep_minutes), cook_minutes.eq(snapshot.cook_minutes),
+                 total_minutes.eq(snapshot.total_minutes), difficulty.eq(&snapshot.difficulty)))
+        .execute(con)?;
+    Ok(())
+}
 
-            let delete_ids: Vec<i32> = to_delete.into_iter().map(|x| *(ingredient_to_id.get(x.as_str()).unwrap()))
-                .collect();
-            diesel::delete(recipe_ingredient.filter(schema::recipe_ingredient::recipe_id.eq(path)).filter(ingredient_id.eq_any(&delete_ids)))
-                .execute(x).unwrap();
+/// Restoring is an edit of its own, later revisions stay in the history.
+async fn restore_revision(State(search_state): State<SearchState>, session: WritableSession, Path(path): Path<i32>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let revision = {
+        use recipemanagement::schema::recipe_revision::dsl::*;
+        recipe_revision.filter(revision_id.eq(path)).first::<RecipeRevision>(con)
+    };
+    let Ok(revision) = revision else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let snapshot = RecipeSnapshot::from(&revision);
+    let form = PutRecipe {
+        name: snapshot.recipe_name.clone().unwrap_or_default(),
+        book: snapshot.book_id,
+        course: snapshot.course_id,
+        season: snapshot.primary_season,
+        ingredients: Some(snapshot.ingredients.join("\n")),
+        page: snapshot.page.map(|x| x.to_string()),
+        recipe_url: snapshot.recipe_url.clone(),
+        recipe_text: Some(snapshot.content.clone()),
+        tags: Some(snapshot.tags.join(", ")),
+        language: snapshot.language.clone(),
+        servings: snapshot.servings.map(|x| x.to_string()),
+        prep_minutes: snapshot.prep_minutes.map(|x| x.to_string()),
+        cook_minutes: snapshot.cook_minutes.map(|x| x.to_string()),
+        total_minutes: snapshot.total_minutes.map(|x| x.to_string()),
+        difficulty: snapshot.difficulty.clone(),
+        json_ld: None,
+    };
+    con.transaction::<_, Error, _>(|x| {
+        let before = query_recipe_snapshot(x, revision.recipe_id);
+        save_recipe(x, revision.recipe_id, form)?;
+        record_revision(x, revision.recipe_id, maybe_user_id.unwrap(), before)
+    }).unwrap();
 
-            let start_id: i32 = ingredient.select(max(id))
-                .first::<Option<i32>>(x)
-                .unwrap()
-                .map(|x| x + 1)
-                .unwrap_or(1);
+    update_index_with_users(&search_state, con, revision.recipe_id);
+    Redirect::to(format!("/recipe/detail/{}", revision.recipe_id).as_str()).into_response()
+}
 
+async fn put_recipe(State(search_state): State<SearchState>, session: WritableSession, Path(path): Path<i32>, Form(form): Form<PutRecipe>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
 
-            let new_ingredients: Vec<InsertIngredient> = to_insert.iter().enumerate()
-                .map(|(i, x)| InsertIngredient { id: Some(i as i32 + start_id), name: x.to_string() })
-                .collect();
-            let new_refs: Vec<InsertRecipeIngredient> = to_insert.iter()
-                .enumerate()
-                .map(|(i, _x)| InsertRecipeIngredient { recipe_id: path, ingredient_id: i as i32 + start_id })
-                .collect();
+    let con = &mut database::establish_connection();
 
-            diesel::insert_into(ingredient)
-                .values(new_ingredients)
-                .execute(x)
-                .unwrap();
-            diesel::insert_into(recipe_ingredient)
-                .values(new_refs)
-                .execute(x)
-                .unwrap();
-        }
+    let transaction_res = con.transaction::<_, Error, _>(|x| {
+        let before = query_recipe_snapshot(x, path);
+        save_recipe(x, path, form)?;
+        record_revision(x, path, maybe_user_id.unwrap(), before)
+    });
+    if transaction_res.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
 
+    update_index_with_users(&search_state, con, path);
+    Redirect::to(format!("/recipe/detail/{}", path).as_str()).into_response()
+}
 
-        Ok(())
+async fn cook_mode(session: WritableSession, Path(path): Path<i32>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
     }
-    );
+    let con = &mut database::establish_connection();
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    let Some(snapshot) = query_recipe_snapshot(con, path) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let steps = cook_steps(snapshot.content.as_str()).into_iter()
+        .map(|x| (render_markdown(x.as_str()), find_timers(x.as_str())))
+        .collect();
+    let ingredients_html = render_recipe_text(snapshot.content.as_str()).into_iter()
+        .find(|x| x.0 == Some(ESection::Ingredients))
+        .map(|x| x.1);
 
-    let vec1 = query_all_recipes(con);
-    let x1 = vec1.into_iter().filter(|x| x.recipe.recipe_id.expect("Recipe should have an id") == path)
-        .find_or_first(|x| 1 > 0).expect("The recipe should be found by id");
+    Html(CookMode {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: snapshot.recipe_name.as_deref().unwrap_or_default(),
+        recipe_id: path,
+        steps,
+        ingredients_html,
+        ingredients: snapshot.ingredients,
+        user_id: maybe_user_id,
+        build_version: env!("VERGEN_GIT_SHA"),
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+#[derive(Deserialize)]
+struct PrintOptions {
+    format: Option<String>,
+}
+
+fn print_recipe(con: &mut SqliteConnection, cur_recipe_id: i32) -> Option<PrintRecipe> {
+    let snapshot = query_recipe_snapshot(con, cur_recipe_id)?;
+    let cur_course = {
+        use recipemanagement::schema::course::dsl::*;
+        course.filter(course_id.eq(snapshot.course_id)).first::<QCourse>(con).ok().and_then(|x| x.course_name)
+    };
+    let cur_book = snapshot.book_id.and_then(|x| {
+        use recipemanagement::schema::book::dsl::*;
+        book.filter(book_id.eq(x)).first::<QBook>(con).ok().and_then(|y| y.book_name)
+    });
+    Some(PrintRecipe {
+        name: snapshot.recipe_name.unwrap_or_default(),
+        course: cur_course.unwrap_or_default(),
+        season: query_season_names(con).remove(&snapshot.primary_season).unwrap_or_default(),
+        reference: book_reference(cur_book.as_deref(), snapshot.page),
+        ingredients: snapshot.ingredients,
+        text: snapshot.content,
+    })
+}
 
-    update_index(&search_state, x1);
-    Redirect::to(format!("/recipe/detail/{}", path).as_str())
+fn pdf_response(con: &mut SqliteConnection, title: &str, recipe_ids: Vec<i32>, format: EPaperFormat) -> Response {
+    let recipes: Vec<PrintRecipe> = recipe_ids.into_iter().filter_map(|x| print_recipe(con, x)).collect();
+    let file_name: String = title.chars()
+        .map(|x| if x.is_ascii_alphanumeric() { x } else { '-' })
+        .collect();
+    ([
+        (header::CONTENT_TYPE, "application/pdf".to_string()),
+        (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.pdf\"", file_name)),
+    ], recipes_pdf(title, &recipes, format)).into_response()
+}
+
+async fn print_card(session: WritableSession, Path(path): Path<i32>, Query(options): Query<PrintOptions>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let Some(cur_recipe) = print_recipe(con, path) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let recipe_sections = render_recipe_text(cur_recipe.text.as_str());
+
+    Html(PrintCard {
+        recipe_id: path,
+        has_ingredient_section: recipe_sections.iter().any(|x| x.0 == Some(ESection::Ingredients)),
+        recipe: cur_recipe,
+        recipe_sections,
+        format: EPaperFormat::from_code(options.format.as_deref()),
+        formats: EPaperFormat::get_formats(),
+    }.get()).into_response()
+}
+
+async fn recipe_pdf(session: WritableSession, Path(path): Path<i32>, Query(options): Query<PrintOptions>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let Some(cur_recipe) = print_recipe(con, path) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    pdf_response(con, cur_recipe.name.as_str(), vec![path], EPaperFormat::from_code(options.format.as_deref()))
+}
+
+async fn course_pdf(session: WritableSession, Path(path): Path<String>, Query(options): Query<PrintOptions>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let Ok(found) = course.filter(schema::course::course_name.eq(&path)).first::<QCourse>(con) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    use recipemanagement::schema::recipe::dsl::*;
+    let recipe_ids: Vec<Option<i32>> = recipe.filter(schema::recipe::course_id.eq(found.course_id.unwrap()))
+        .order(recipe_name.asc())
+        .select(recipe_id)
+        .load::<Option<i32>>(con)
+        .unwrap();
+    pdf_response(con, path.as_str(), recipe_ids.into_iter().flatten().collect(), EPaperFormat::from_code(options.format.as_deref()))
+}
+
+async fn collection_pdf(session: WritableSession, Path(path): Path<i32>, Query(options): Query<PrintOptions>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let Some(found) = query_accessible_collections(con, maybe_user_id.unwrap()).into_iter().find(|x| x.collection_id == Some(path)) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    use recipemanagement::schema::collection_recipe::dsl::*;
+    let recipe_ids: Vec<i32> = collection_recipe.filter(collection_id.eq(path))
+        .order(position.asc())
+        .select(recipe_id)
+        .load::<i32>(con)
+        .unwrap();
+    pdf_response(con, found.collection_name.as_str(), recipe_ids, EPaperFormat::from_code(options.format.as_deref()))
+}
+
+async fn search_pdf(State(search_state): State<SearchState>, session: WritableSession, RawQuery(query): RawQuery, Query(options): Query<PrintOptions>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let Some(prefill) = SearchPrefill::from_query_string(query.unwrap_or_default().as_str()) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let outcome = search_toggle::search(&prefill, con, &search_state.index, maybe_user_id.unwrap());
+    let recipe_ids = outcome.recipes.into_iter().filter_map(|x| x.recipe_id).collect();
+    pdf_response(con, "Search", recipe_ids, EPaperFormat::from_code(options.format.as_deref()))
+}
+
+#[derive(Deserialize)]
+struct PreviewRecipeText {
+    recipe_text: String,
+}
+
+async fn preview_recipe_text(session: WritableSession, Form(form): Form<PreviewRecipeText>) -> Response {
+    if get_user_id(session).is_none() {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    Html(RecipeTextPreview {
+        recipe_sections: render_recipe_text(form.recipe_text.as_str()),
+    }.get()).into_response()
 }
 
 async fn toggle_tried(session: WritableSession, Path(path): Path<i32>) -> StatusCode {
@@ -821,7 +1286,7 @@ async fn toggle_tried(session: WritableSession, Path(path): Path<i32>) -> Status
         return StatusCode::UNAUTHORIZED;
     }
     let connection = &mut database::establish_connection();
-    let _transaction_res = connection.transaction::<_, Error, _>(|con| {
+    let transaction_res = connection.transaction::<_, Error, _>(|con| {
         use recipemanagement::schema::tried::dsl::*;
         let already_exists = select(
             exists(
@@ -842,7 +1307,9 @@ async fn toggle_tried(session: WritableSession, Path(path): Path<i32>) -> Status
 
         return Ok(());
     });
-
+    if transaction_res.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
 
     StatusCode::OK
 }
@@ -866,9 +1333,11 @@ fn query_for_recipe_detail<'a, 'b>(con: &mut SqliteConnection, path: i32, cur_us
 
     use recipemanagement::schema::book::dsl::*;
 
-    let disp_book: Option<String> = book.filter(schema::book::dsl::book_id.eq(das_recipe.unwrap().book_id)).load::<QBook>(con).expect("Expected DB to work")
+    let recipe_book: Option<QBook> = book.filter(schema::book::dsl::book_id.eq(das_recipe.unwrap().book_id)).load::<QBook>(con).expect("Expected DB to work")
         .first()
-        .map(|x| x.book_name.as_ref().unwrap().clone());
+        .cloned();
+    let disp_book: Option<String> = recipe_book.as_ref().map(|x| x.book_name.as_ref().unwrap().clone());
+    let recipe_language = ERecipeLanguage::resolve(das_recipe.unwrap().language.as_ref(), recipe_book.as_ref().and_then(|x| x.language.as_ref()));
 
     let courses: Vec<QCourse> = course.load::<QCourse>(con).expect("Expected DB to work");
     let res_recipe = das_recipe.unwrap();
@@ -918,10 +1387,11 @@ WHERE recipe_id={}", path);
         ingredients,
         title: res_recipe.recipe_name.clone().unwrap(),
         book_name: disp_book,
-        season: ESeason::get_by_db_id(res_recipe.primary_season),
+        language: recipe_language,
         tried: already_exists,
         comments,
         recipe_text: recipe_text_disp,
+        tags: query_recipes_to_tags(con).remove(&path).unwrap_or_default(),
     }))
 }
 
@@ -932,10 +1402,11 @@ struct RecipeDetailQuery {
     ingredients: Vec<String>,
     title: String,
     book_name: Option<String>,
-    season: ESeason,
+    language: ERecipeLanguage,
     tried: bool,
     comments: Vec<Comment>,
-    recipe_text: String
+    recipe_text: String,
+    tags: Vec<String>,
 
 }
 
@@ -948,23 +1419,202 @@ async fn recipe_detail(session: WritableSession, Path(path): Path<i32>) -> Respo
     let con = &mut database::establish_connection();
     let res = con.transaction(|x| query_for_recipe_detail(x, path, maybe_user_id.unwrap()));
     let build_version = env!("VERGEN_GIT_SHA");
-
-    Html(res.ok()
-        .unwrap()
-        .map(|x| RecipeDetail {
-            courses: &x.courses,
-            course: x.course.as_str(),
-            recipe: &x.recipe,
-            ingredients: x.ingredients,
-            title: x.title.as_str(),
-            book_name: &x.book_name,
-            season: x.season,
-            tried: x.tried,
-            comments: x.comments,
-            recipe_text: x.recipe_text,
-            user_id: maybe_user_id,
-            build_version,
-            debug_compilation: cfg!(debug_assertions),
+    let containing_ids: HashSet<i32> = {
+        use recipemanagement::schema::collection_recipe::dsl::*;
+        collection_recipe.filter(recipe_id.eq(path))
+            .select(collection_id)
+            .load::<i32>(con)
+            .unwrap()
+            .into_iter()
+            .collect()
+    };
+    let recipe_collections: Vec<QCollection> = query_accessible_collections(con, maybe_user_id.unwrap()).into_iter()
+        .filter(|x| containing_ids.contains(&x.collection_id.unwrap()))
+        .collect();
+    let overrides = query_diet_overrides(con).remove(&path).unwrap_or_default();
+    let diet_overrides: Vec<(EDiet, Option<bool>)> = EDiet::get_diets().into_iter()
+        .map(|x| (x, overrides.get(&x).copied()))
+        .collect();
+    let own_rating: Option<RecipeRating> = {
+        use recipemanagement::schema::recipe_rating::dsl::*;
+        recipe_rating.filter(user_id.eq(maybe_user_id.unwrap()))
+            .filter(recipe_id.eq(path))
+            .first::<RecipeRating>(con)
+            .ok()
+    };
+    let recipe_images: Vec<RecipeImage> = {
+        use recipemanagement::schema::recipe_image::dsl::*;
+        recipe_image.filter(recipe_id.eq(path))
+            .order((is_cover.desc(), position))
+            .load::<RecipeImage>(con)
+            .unwrap()
+    };
+    let display_names = query_display_names(con);
+    let cooking_log: Vec<(Cooked, String)> = {
+        use recipemanagement::schema::cooked::dsl::*;
+        cooked.filter(recipe_id.eq(path))
+            .order((cooked_on.desc(), cooked_id.desc()))
+            .load::<Cooked>(con)
+            .unwrap()
+    }.into_iter()
+        .map(|x| {
+            let cook_name = display_names.get(&x.user_id).cloned().unwrap_or("-".to_string());
+            (x, cook_name)
+        })
+        .collect();
+    let course_names: HashMap<i32, String> = course.load::<QCourse>(con).unwrap().into_iter()
+        .map(|x| (x.course_id.unwrap(), x.course_name.unwrap_or_default()))
+        .collect();
+    let book_names: HashMap<i32, String> = {
+        use recipemanagement::schema::book::dsl::*;
+        book.load::<QBook>(con).unwrap().into_iter()
+            .map(|x| (x.book_id.unwrap(), x.book_name.unwrap_or_default()))
+            .collect()
+    };
+    let all_seasons = query_seasons(con);
+    let season_names: HashMap<i32, String> = all_seasons.iter().map(|x| (x.season_id, x.name.clone())).collect();
+    let revisions = revision_entries(&query_revisions(con, path), &display_names, &course_names, &book_names, &season_names);
+    let season_entries = query_recipe_seasons(con).remove(&path).unwrap_or_default();
+    let seasons: Vec<(Season, Option<MonthRange>)> = season_entries.iter()
+        .filter_map(|x| all_seasons.iter().find(|y| y.season_id == x.season_id).map(|y| (y.clone(), x.months)))
+        .collect();
+    let season_options: Vec<(Season, bool, Option<u32>, Option<u32>)> = all_seasons.into_iter()
+        .map(|x| {
+            let entry = season_entries.iter().find(|y| y.season_id == x.season_id);
+            let cur_months = entry.and_then(|y| y.months);
+            (x, entry.is_some(), cur_months.map(|y| y.start), cur_months.map(|y| y.end))
+        })
+        .collect();
+    let recipe_names = query_recipe_names(con);
+    let recipe_links = query_recipe_links(con);
+    let components: Vec<ComponentRecipe> = component_ids(path, &recipe_links).into_iter()
+        .map(|x| {
+            let text: String = {
+                use recipemanagement::schema::recipe_text::dsl::*;
+                recipe_text.filter(recipe_id.eq(x)).select(content).first::<String>(con).unwrap_or_default()
+            };
+            let ingredient_names: Vec<String> = {
+                use recipemanagement::schema::recipe_ingredient::dsl::*;
+                use recipemanagement::schema::ingredient::dsl::*;
+                recipe_ingredient.inner_join(ingredient.on(id.eq(ingredient_id.nullable())))
+                    .filter(recipe_id.eq(x))
+                    .select(name)
+                    .load::<Option<String>>(con)
+                    .unwrap()
+                    .into_iter()
+                    .flatten()
+                    .sorted()
+                    .collect()
+            };
+            ComponentRecipe {
+                recipe_id: x,
+                name: recipe_names.get(&x).cloned().unwrap_or_default(),
+                ingredients: ingredient_names,
+                sections: render_recipe_text(text.as_str()),
+            }
+        })
+        .collect();
+    let food_nutrients = query_ingredient_nutrients(con);
+    let nutrition_rows: Vec<(i32, i32, String, Option<f32>)> = {
+        use recipemanagement::schema::recipe_ingredient::dsl::*;
+        use recipemanagement::schema::ingredient::dsl::*;
+        let recipe_ids: Vec<i32> = std::iter::once(path).chain(components.iter().map(|x| x.recipe_id)).collect();
+        recipe_ingredient.inner_join(ingredient.on(id.eq(ingredient_id.nullable())))
+            .filter(recipe_id.eq_any(&recipe_ids))
+            .select((recipe_id, ingredient_id, name, amount_grams))
+            .load::<(i32, i32, Option<String>, Option<f32>)>(con)
+            .unwrap()
+            .into_iter()
+            .map(|(cur_recipe_id, cur_ingredient_id, cur_name, grams)| (cur_recipe_id, cur_ingredient_id, cur_name.unwrap_or_default(), grams))
+            .sorted_by_key(|x| x.2.to_lowercase())
+            .collect()
+    };
+    let cur_servings: Option<i32> = {
+        use recipemanagement::schema::recipe::dsl::*;
+        recipe.filter(recipe_id.eq(path)).select(servings).first::<Option<i32>>(con).ok().flatten()
+    };
+    let nutrition = estimate_nutrition(&nutrition_rows.iter()
+        .map(|x| NutritionInput { name: x.2.clone(), amount_grams: x.3, per_100g: food_nutrients.get(&x.1).map(Nutrients::from) })
+        .collect::<Vec<NutritionInput>>(), cur_servings);
+    let quantities: Vec<(i32, String, Option<f32>)> = nutrition_rows.into_iter()
+        .filter(|x| x.0 == path)
+        .map(|x| (x.1, x.2, x.3))
+        .collect();
+    let own_equipment: HashSet<i32> = {
+        use recipemanagement::schema::recipe_equipment::dsl::*;
+        recipe_equipment.filter(recipe_id.eq(path)).select(equipment_id).load::<i32>(con).unwrap().into_iter().collect()
+    };
+    let owned_equipment = query_user_equipment_ids(con, maybe_user_id.unwrap());
+    let needed_equipment = query_recipes_to_equipment_ids(con).remove(&path).unwrap_or_default();
+    let equipment_options: Vec<(Equipment, bool)> = query_equipment(con).into_iter()
+        .map(|x| {
+            let is_selected = own_equipment.contains(&x.equipment_id.unwrap());
+            (x, is_selected)
+        })
+        .collect();
+    let required_equipment: Vec<(String, bool)> = equipment_options.iter()
+        .filter(|x| needed_equipment.contains(&x.0.equipment_id.unwrap()))
+        .map(|x| (x.0.name.clone(), owned_equipment.contains(&x.0.equipment_id.unwrap())))
+        .collect();
+    let links: Vec<(&'static str, Vec<(LinkedRecipe, String)>)> = linked_recipes(path, &recipe_links).into_iter()
+        .map(|(label, group)| (label, group.into_iter()
+            .map(|x| (x, recipe_names.get(&x.recipe_id).cloned().unwrap_or_default()))
+            .collect()))
+        .collect();
+    let recipe_choices: Vec<(i32, String)> = recipe_names.into_iter()
+        .filter(|x| x.0 != path)
+        .sorted_by(|a, b| a.1.to_lowercase().cmp(&b.1.to_lowercase()))
+        .collect();
+
+    Html(res.ok()
+        .unwrap()
+        .map(|x| RecipeDetail {
+            courses: &x.courses,
+            saved_searches: query_saved_searches(maybe_user_id, con),
+            course: x.course.as_str(),
+            recipe: &x.recipe,
+            ingredients: x.ingredients,
+            title: x.title.as_str(),
+            book_name: &x.book_name,
+            seasons,
+            season_options,
+            months: months(),
+            language: x.language,
+            tried: x.tried,
+            comments: thread_comments(x.comments).into_iter()
+                .map(|(comment, depth)| {
+                    let author = display_names.get(&comment.user_id).cloned().unwrap_or_default();
+                    (comment, depth, author)
+                })
+                .collect(),
+            is_admin: user_is_admin(maybe_user_id.unwrap(), con),
+            recipe_sections: render_recipe_text(x.recipe_text.as_str()),
+            recipe_text: x.recipe_text,
+            tags: x.tags,
+            collections: query_own_collections(maybe_user_id, con),
+            recipe_collections,
+            diet: query_recipe_diets(con).remove(&path).unwrap_or_default(),
+            diet_overrides,
+            rating_summary: query_rating_summaries(con).remove(&path).unwrap_or_default(),
+            own_rating,
+            cooking_log,
+            images: recipe_images,
+            revisions,
+            links,
+            components,
+            nutrition,
+            cost: query_recipe_cost(con, path, Local::now().date_naive()),
+            quantities,
+            times: describe_times(x.recipe.prep_minutes, x.recipe.cook_minutes, x.recipe.total_minutes),
+            difficulty: x.recipe.difficulty.as_deref().and_then(EDifficulty::from_code),
+            equipment_options,
+            required_equipment,
+            link_types: ELinkType::get_link_types(),
+            recipe_choices,
+            today: Local::now().date_naive().to_string(),
+            user_id: maybe_user_id,
+            build_version,
+            debug_compilation: cfg!(debug_assertions),
         }.get())
         .unwrap_or("404".to_string())
     ).into_response()
@@ -973,6 +1623,7 @@ async fn recipe_detail(session: WritableSession, Path(path): Path<i32>) -> Respo
 #[derive(Deserialize)]
 struct PostComment {
     comment: String,
+    parent_id: Option<i32>,
 }
 
 async fn post_comment(session: WritableSession, Path(path): Path<i32>, Form(form): Form<PostComment>) -> Response {
@@ -985,11 +1636,21 @@ async fn post_comment(session: WritableSession, Path(path): Path<i32>, Form(form
 
     if !form.comment.trim().is_empty() {
         let cur_user_id = maybe_user_id.unwrap();
-        
+        if let Some(reply_to) = form.parent_id {
+            use recipemanagement::schema::recipe_comment::dsl::*;
+            let same_recipe = select(exists(
+                recipe_comment.filter(comment_id.eq(reply_to)).filter(recipe_id.eq(path))
+            )).get_result::<bool>(con).unwrap();
+            if !same_recipe {
+                return StatusCode::BAD_REQUEST.into_response();
+            }
+        }
+
         let insert_comment = InsertComment {
             user_id: cur_user_id,
             recipe_id: path,
             content: form.comment.trim().to_string(),
+            parent_id: form.parent_id,
         };
 
         diesel::insert_into(schema::recipe_comment::table)
@@ -1002,6 +1663,133 @@ async fn post_comment(session: WritableSession, Path(path): Path<i32>, Form(form
     Redirect::to(format!("/recipe/detail/{}", path).as_str()).into_response()
 }
 
+/// Authors may change their own comments, admins may change any of them.
+fn editable_comment(con: &mut SqliteConnection, query_comment_id: i32, query_user_id: i32) -> Result<Comment, StatusCode> {
+    use recipemanagement::schema::recipe_comment::dsl::*;
+    let entry = recipe_comment.filter(comment_id.eq(query_comment_id))
+        .first::<Comment>(con)
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+    if entry.user_id != query_user_id && !user_is_admin(query_user_id, con) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(entry)
+}
+
+async fn edit_comment(session: WritableSession, Path(path): Path<i32>, Form(form): Form<PostComment>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let entry = match editable_comment(con, path, maybe_user_id.unwrap()) {
+        Ok(x) => x,
+        Err(status) => return status.into_response(),
+    };
+    if !form.comment.trim().is_empty() {
+        sql_query("UPDATE recipe_comment SET content = ?, modified_at = datetime('now','localtime') WHERE comment_id = ?")
+            .bind::<Text, _>(form.comment.trim())
+            .bind::<Integer, _>(path)
+            .execute(con)
+            .unwrap();
+    }
+
+    Redirect::to(format!("/recipe/detail/{}#comment-{}", entry.recipe_id, path).as_str()).into_response()
+}
+
+async fn delete_comment(session: WritableSession, Path(path): Path<i32>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let entry = match editable_comment(con, path, maybe_user_id.unwrap()) {
+        Ok(x) => x,
+        Err(status) => return status.into_response(),
+    };
+    con.transaction::<_, Error, _>(|con| {
+        use recipemanagement::schema::recipe_comment::dsl::*;
+        let siblings = recipe_comment.filter(recipe_id.eq(entry.recipe_id)).load::<Comment>(con)?;
+        diesel::delete(recipe_comment.filter(comment_id.eq_any(comment_and_replies(&siblings, path)))).execute(con)?;
+        Ok(())
+    }).unwrap();
+
+    Redirect::to(format!("/recipe/detail/{}", entry.recipe_id).as_str()).into_response()
+}
+
+async fn settings_page(session: WritableSession) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    use recipemanagement::schema::user::dsl::*;
+    let cur_user = user.filter(id.eq(maybe_user_id.unwrap())).first::<User>(con).unwrap();
+    let owned = query_user_equipment_ids(con, maybe_user_id.unwrap());
+
+    Html(Settings {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: "Settings",
+        email: cur_user.email,
+        display_name: cur_user.display_name.unwrap_or_default(),
+        equipment: query_equipment(con).into_iter()
+            .map(|x| {
+                let is_owned = owned.contains(&x.equipment_id.unwrap());
+                (x, is_owned)
+            })
+            .collect(),
+        user_id: maybe_user_id,
+        build_version: env!("VERGEN_GIT_SHA"),
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+#[derive(Deserialize)]
+struct PostSettings {
+    display_name: String,
+}
+
+async fn post_settings(session: WritableSession, Form(form): Form<PostSettings>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    use recipemanagement::schema::user::dsl::*;
+    let new_name = Some(form.display_name.trim().to_string()).filter(|x| !x.is_empty());
+    diesel::update(user.filter(id.eq(maybe_user_id.unwrap())))
+        .set(display_name.eq(new_name))
+        .execute(con)
+        .unwrap();
+
+    Redirect::to("/settings").into_response()
+}
+
+async fn post_settings_equipment(session: WritableSession, Form(form): Form<HashMap<String, String>>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let selected: Vec<i32> = query_equipment(con).into_iter()
+        .filter_map(|x| x.equipment_id)
+        .filter(|x| form.contains_key(&format!("equipment{}", x)))
+        .collect();
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::user_equipment::dsl::*;
+        diesel::delete(user_equipment.filter(user_id.eq(maybe_user_id.unwrap()))).execute(x)?;
+        for cur_equipment_id in selected {
+            diesel::insert_into(user_equipment)
+                .values((user_id.eq(maybe_user_id.unwrap()), equipment_id.eq(cur_equipment_id)))
+                .execute(x)?;
+        }
+        Ok(())
+    }).unwrap();
+
+    Redirect::to("/settings").into_response()
+}
+
 fn get_user_id(mut session: WritableSession) -> Option<i32> {
     let maybe_user_id = session.get::<i32>("user_id");
     if maybe_user_id.is_none() {
@@ -1015,3 +1803,1554 @@ fn get_user_id(mut session: WritableSession) -> Option<i32> {
     }
     maybe_user_id
 }
+
+async fn synonym_admin(session: WritableSession) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    let groups: Vec<(i32, String)> = query_synonym_groups(con).into_iter()
+        .map(|(group, terms)| (group, terms.join(", ")))
+        .collect();
+    let build_version = env!("VERGEN_GIT_SHA");
+
+    Html(SynonymAdmin {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: "Synonyms",
+        groups,
+        user_id: maybe_user_id,
+        build_version,
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+#[derive(Deserialize)]
+struct PostSynonymGroup {
+    group_id: Option<i32>,
+    terms: String,
+    delete: Option<String>,
+}
+
+async fn post_synonym_group(State(search_state): State<SearchState>, session: WritableSession, Form(form): Form<PostSynonymGroup>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let terms: Vec<String> = form.terms.split(',')
+        .map(|x| x.trim().to_lowercase())
+        .filter(|x| !x.is_empty())
+        .unique()
+        .collect();
+
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::synonym::dsl::*;
+        let cur_group_id = match form.group_id {
+            Some(i) => i,
+            None => synonym.select(max(group_id)).first::<Option<i32>>(x)?.map(|y| y + 1).unwrap_or(1),
+        };
+        diesel::delete(synonym.filter(group_id.eq(cur_group_id))).execute(x)?;
+        if form.delete.is_none() {
+            let insert_synonyms: Vec<InsertSynonym> = terms.iter()
+                .map(|y| InsertSynonym { group_id: cur_group_id, term: y.clone() })
+                .collect();
+            // a term can only live in one group, saving it here moves it out of its old group
+            diesel::replace_into(synonym)
+                .values(insert_synonyms)
+                .execute(x)?;
+        }
+        Ok(())
+    }).unwrap();
+
+    let synonym_groups = query_synonym_groups(con).into_iter().map(|x| x.1).collect();
+    reload_synonyms(&search_state, synonym_groups);
+    nuke_and_rebuild_index(&search_state);
+
+    Redirect::to("/admin/synonyms").into_response()
+}
+
+fn query_own_collections(query_user_id: Option<i32>, con: &mut SqliteConnection) -> Vec<QCollection> {
+    let Some(cur_user_id) = query_user_id else { return vec![] };
+    use recipemanagement::schema::collection::dsl::*;
+    collection.filter(user_id.eq(cur_user_id))
+        .order(collection_name.asc())
+        .load::<QCollection>(con)
+        .unwrap()
+}
+
+/// Only the owner may change a collection, users it is shared with can look at it.
+fn query_owned_collection(query_collection_id: i32, query_user_id: i32, con: &mut SqliteConnection) -> Option<QCollection> {
+    use recipemanagement::schema::collection::dsl::*;
+    collection.filter(collection_id.eq(query_collection_id))
+        .filter(user_id.eq(query_user_id))
+        .first::<QCollection>(con)
+        .optional()
+        .unwrap()
+}
+
+fn user_email(query_user_id: i32, con: &mut SqliteConnection) -> String {
+    use recipemanagement::schema::user::dsl::*;
+    user.filter(id.eq(query_user_id))
+        .select(email)
+        .first::<String>(con)
+        .unwrap_or("-".to_string())
+}
+
+async fn collection_list(session: WritableSession) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    let (own, others): (Vec<QCollection>, Vec<QCollection>) = query_accessible_collections(con, maybe_user_id.unwrap()).into_iter()
+        .partition(|x| x.user_id == maybe_user_id.unwrap());
+    let shared: Vec<(QCollection, String)> = others.into_iter()
+        .map(|x| {
+            let owner = user_email(x.user_id, con);
+            (x, owner)
+        })
+        .collect();
+    let build_version = env!("VERGEN_GIT_SHA");
+
+    Html(CollectionList {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: "Collections",
+        own,
+        shared,
+        user_id: maybe_user_id,
+        build_version,
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+#[derive(Deserialize)]
+struct PostCollection {
+    collection_name: String,
+}
+
+async fn post_collection(session: WritableSession, Form(form): Form<PostCollection>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let cur_name = form.collection_name.trim().to_string();
+    if cur_name.is_empty() {
+        return Redirect::to("/collections").into_response();
+    }
+    let con = &mut database::establish_connection();
+    use recipemanagement::schema::collection::dsl::*;
+    diesel::insert_or_ignore_into(collection)
+        .values(InsertCollection { user_id: maybe_user_id.unwrap(), collection_name: cur_name.clone() })
+        .execute(con)
+        .unwrap();
+    let cur_id = collection.filter(user_id.eq(maybe_user_id.unwrap()))
+        .filter(collection_name.eq(cur_name))
+        .select(collection_id)
+        .first::<Option<i32>>(con)
+        .unwrap()
+        .unwrap();
+
+    Redirect::to(format!("/collection/{}", cur_id).as_str()).into_response()
+}
+
+async fn collection_detail(session: WritableSession, Path(path): Path<i32>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let found = query_accessible_collections(con, maybe_user_id.unwrap()).into_iter()
+        .find(|x| x.collection_id == Some(path));
+    let Some(found) = found else {
+        return Redirect::to("/collections").into_response();
+    };
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    use recipemanagement::schema::book::dsl::*;
+    let id_to_book_name: HashMap<i32, String> = book.load::<QBook>(con).unwrap().iter()
+        .map(|x| (x.book_id.unwrap(), x.book_name.clone().unwrap()))
+        .collect();
+    use recipemanagement::schema::collection_recipe::dsl::*;
+    use recipemanagement::schema::recipe::dsl::*;
+    let entries: Vec<(FullRecipe, Option<String>)> = collection_recipe
+        .inner_join(recipe)
+        .filter(schema::collection_recipe::collection_id.eq(path))
+        .order(position.asc())
+        .select((schema::recipe::all_columns, note))
+        .load::<(FullRecipe, Option<String>)>(con)
+        .unwrap();
+    use recipemanagement::schema::collection_share::dsl::*;
+    use recipemanagement::schema::user::dsl::*;
+    let shared_with: Vec<String> = collection_share
+        .inner_join(user)
+        .filter(schema::collection_share::collection_id.eq(path))
+        .select(email)
+        .order(email.asc())
+        .load::<String>(con)
+        .unwrap();
+    let owner_email = user_email(found.user_id, con);
+    let build_version = env!("VERGEN_GIT_SHA");
+    let page_title = found.collection_name.clone();
+
+    Html(CollectionDetail {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: page_title.as_str(),
+        can_edit: found.user_id == maybe_user_id.unwrap(),
+        collection: found,
+        entries,
+        owner_email,
+        shared_with,
+        id_to_book_name,
+        user_id: maybe_user_id,
+        build_version,
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+async fn add_to_collection(State(search_state): State<SearchState>, session: WritableSession, Path((path, path_recipe_id)): Path<(i32, i32)>) -> StatusCode {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return StatusCode::UNAUTHORIZED;
+    }
+    let con = &mut database::establish_connection();
+    if query_owned_collection(path, maybe_user_id.unwrap(), con).is_none() {
+        return StatusCode::FORBIDDEN;
+    }
+    let known_recipe = {
+        use recipemanagement::schema::recipe::dsl::*;
+        select(exists(recipe.filter(recipe_id.eq(path_recipe_id)))).get_result::<bool>(con).unwrap()
+    };
+    if !known_recipe {
+        return StatusCode::NOT_FOUND;
+    }
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::collection_recipe::dsl::*;
+        let next_position = collection_recipe.filter(collection_id.eq(path))
+            .select(max(position))
+            .first::<Option<i32>>(x)?
+            .map(|y| y + 1)
+            .unwrap_or(0);
+        diesel::insert_or_ignore_into(collection_recipe)
+            .values(InsertCollectionRecipe { collection_id: path, recipe_id: path_recipe_id, position: next_position })
+            .execute(x)?;
+        Ok(())
+    }).unwrap();
+    update_index_for(&search_state, con, &[path_recipe_id]);
+
+    StatusCode::OK
+}
+
+#[derive(Deserialize)]
+struct PostCollectionEntry {
+    note: Option<String>,
+    action: String,
+}
+
+async fn post_collection_entry(State(search_state): State<SearchState>, session: WritableSession, Path((path, path_recipe_id)): Path<(i32, i32)>, Form(form): Form<PostCollectionEntry>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if query_owned_collection(path, maybe_user_id.unwrap(), con).is_none() {
+        return Redirect::to("/collections").into_response();
+    }
+    let in_collection = {
+        use recipemanagement::schema::collection_recipe::dsl::*;
+        select(exists(collection_recipe.filter(collection_id.eq(path)).filter(recipe_id.eq(path_recipe_id)))).get_result::<bool>(con).unwrap()
+    };
+    if !in_collection {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::collection_recipe::dsl::*;
+        let entry = collection_recipe.filter(collection_id.eq(path));
+        match form.action.as_str() {
+            "remove" => {
+                diesel::delete(entry.filter(recipe_id.eq(path_recipe_id))).execute(x)?;
+            }
+            "up" | "down" => {
+                let ordered: Vec<CollectionRecipe> = entry.order(position.asc()).load::<CollectionRecipe>(x)?;
+                let Some(cur) = ordered.iter().position(|y| y.recipe_id == path_recipe_id) else { return Ok(()) };
+                let other = if form.action == "up" { cur.checked_sub(1) } else { Some(cur + 1).filter(|y| *y < ordered.len()) };
+                if let Some(other) = other {
+                    // positions are swapped, gaps left by removed entries don't matter
+                    diesel::update(entry.filter(recipe_id.eq(ordered[cur].recipe_id)))
+                        .set(position.eq(ordered[other].position))
+                        .execute(x)?;
+                    diesel::update(entry.filter(recipe_id.eq(ordered[other].recipe_id)))
+                        .set(position.eq(ordered[cur].position))
+                        .execute(x)?;
+                }
+            }
+            _ => {
+                let cur_note = form.note.as_ref().map(|y| y.trim().to_string()).filter(|y| !y.is_empty());
+                diesel::update(entry.filter(recipe_id.eq(path_recipe_id)))
+                    .set(note.eq(cur_note))
+                    .execute(x)?;
+            }
+        }
+        Ok(())
+    }).unwrap();
+    if form.action == "remove" {
+        update_index_for(&search_state, con, &[path_recipe_id]);
+    }
+
+    Redirect::to(format!("/collection/{}", path).as_str()).into_response()
+}
+
+#[derive(Deserialize)]
+struct PostCollectionShare {
+    email: String,
+    remove: Option<String>,
+}
+
+async fn post_collection_share(session: WritableSession, Path(path): Path<i32>, Form(form): Form<PostCollectionShare>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if query_owned_collection(path, maybe_user_id.unwrap(), con).is_none() {
+        return Redirect::to("/collections").into_response();
+    }
+    let share_user_id: Option<i32> = {
+        use recipemanagement::schema::user::dsl::*;
+        user.filter(email.eq(form.email.trim()))
+            .select(id)
+            .first::<Option<i32>>(con)
+            .optional()
+            .unwrap()
+            .flatten()
+    };
+    use recipemanagement::schema::collection_share::dsl::*;
+    if let Some(share_user_id) = share_user_id.filter(|x| *x != maybe_user_id.unwrap()) {
+        if form.remove.is_some() {
+            diesel::delete(collection_share.filter(collection_id.eq(path)).filter(user_id.eq(share_user_id)))
+                .execute(con)
+                .unwrap();
+        } else {
+            diesel::insert_or_ignore_into(collection_share)
+                .values(InsertCollectionShare { collection_id: path, user_id: share_user_id })
+                .execute(con)
+                .unwrap();
+        }
+    }
+
+    Redirect::to(format!("/collection/{}", path).as_str()).into_response()
+}
+
+async fn delete_collection(State(search_state): State<SearchState>, session: WritableSession, Path(path): Path<i32>) -> Redirect {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login");
+    }
+    let con = &mut database::establish_connection();
+    if query_owned_collection(path, maybe_user_id.unwrap(), con).is_none() {
+        return Redirect::to("/collections");
+    }
+    let members: Vec<i32> = schema::collection_recipe::table.filter(schema::collection_recipe::collection_id.eq(path))
+        .select(schema::collection_recipe::recipe_id)
+        .load::<i32>(con)
+        .unwrap();
+    con.transaction::<_, Error, _>(|x| {
+        diesel::delete(schema::collection_recipe::table.filter(schema::collection_recipe::collection_id.eq(path))).execute(x)?;
+        diesel::delete(schema::collection_share::table.filter(schema::collection_share::collection_id.eq(path))).execute(x)?;
+        diesel::delete(schema::collection::table.filter(schema::collection::collection_id.eq(path))).execute(x)?;
+        Ok(())
+    }).unwrap();
+    update_index_for(&search_state, con, &members);
+
+    Redirect::to("/collections")
+}
+
+/// Replaces the tags of a recipe, unknown tags are created on the way.
+fn save_recipe_tags(con: &mut SqliteConnection, cur_recipe_id: i32, tags: &[String]) -> Result<(), Error> {
+    use recipemanagement::schema::recipe_tag::dsl::*;
+    diesel::delete(recipe_tag.filter(recipe_id.eq(cur_recipe_id))).execute(con)?;
+    for cur_tag in tags {
+        sql_query("INSERT OR IGNORE INTO tag(tag_name) VALUES (?);")
+            .bind::<Text, _>(cur_tag)
+            .execute(con)?;
+        sql_query("INSERT OR IGNORE INTO recipe_tag(recipe_id, tag_id) SELECT ?, tag_id FROM tag WHERE tag_name=?;")
+            .bind::<Integer, _>(cur_recipe_id)
+            .bind::<Text, _>(cur_tag)
+            .execute(con)?;
+    }
+    Ok(())
+}
+
+async fn tag_admin(session: WritableSession) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    use recipemanagement::schema::ingredient::dsl::*;
+    let id_to_ingredient: HashMap<i32, String> = ingredient.load::<Ingredient>(con)
+        .unwrap()
+        .into_iter()
+        .filter(|x| x.name.is_some())
+        .map(|x| (x.id.unwrap(), x.name.unwrap().to_lowercase()))
+        .collect();
+    use recipemanagement::schema::recipe_ingredient::dsl::*;
+    let ingredient_counts = recipe_ingredient.load::<RecipeIngredient>(con)
+        .unwrap()
+        .iter()
+        .filter_map(|x| id_to_ingredient.get(&x.ingredient_id))
+        .counts();
+    let ingredients: Vec<(String, usize)> = id_to_ingredient.values()
+        .unique()
+        .map(|x| (x.clone(), *ingredient_counts.get(x).unwrap_or(&0)))
+        .sorted()
+        .collect();
+    let tag_counts = query_recipes_to_tags(con).into_values().flatten().counts();
+    let tags: Vec<(String, usize)> = schema::tag::table.select(schema::tag::tag_name)
+        .load::<String>(con)
+        .unwrap()
+        .into_iter()
+        .map(|x| {
+            let count = *tag_counts.get(&x).unwrap_or(&0);
+            (x, count)
+        })
+        .sorted()
+        .collect();
+    let build_version = env!("VERGEN_GIT_SHA");
+
+    Html(TagAdmin {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: "Tags",
+        ingredients,
+        tags,
+        user_id: maybe_user_id,
+        build_version,
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+#[derive(Deserialize)]
+struct PostReclassify {
+    names: String,
+    action: String,
+}
+
+async fn post_reclassify(State(search_state): State<SearchState>, session: WritableSession, Form(form): Form<PostReclassify>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let names = parse_tags(form.names.as_str());
+    // only recipes using one of the names change, in either direction
+    let affected: Vec<i32> = {
+        let ingredient_ids: Vec<i32> = schema::ingredient::table.load::<Ingredient>(con)
+            .unwrap()
+            .into_iter()
+            .filter(|x| x.name.as_ref().is_some_and(|y| names.contains(&y.to_lowercase())))
+            .filter_map(|x| x.id)
+            .collect();
+        let tag_ids = schema::tag::table.filter(schema::tag::tag_name.eq_any(&names)).select(schema::tag::tag_id);
+        let mut res: Vec<i32> = schema::recipe_ingredient::table.filter(schema::recipe_ingredient::ingredient_id.eq_any(ingredient_ids))
+            .select(schema::recipe_ingredient::recipe_id)
+            .load::<i32>(con)
+            .unwrap();
+        res.extend(schema::recipe_tag::table.filter(schema::recipe_tag::tag_id.nullable().eq_any(tag_ids))
+            .select(schema::recipe_tag::recipe_id)
+            .load::<i32>(con)
+            .unwrap());
+        res.into_iter().unique().collect()
+    };
+    con.transaction::<_, Error, _>(|x| {
+        for cur_name in names.iter() {
+            if form.action == "to_ingredient" {
+                sql_query("INSERT INTO ingredient(name) SELECT ? WHERE NOT EXISTS(SELECT * FROM ingredient WHERE lower(name)=?);")
+                    .bind::<Text, _>(cur_name)
+                    .bind::<Text, _>(cur_name)
+                    .execute(x)?;
+                sql_query("INSERT OR IGNORE INTO recipe_ingredient(recipe_id, ingredient_id)
+SELECT rt.recipe_id, (SELECT min(id) FROM ingredient WHERE lower(name)=?) FROM recipe_tag rt INNER JOIN tag t ON t.tag_id = rt.tag_id WHERE t.tag_name=?;")
+                    .bind::<Text, _>(cur_name)
+                    .bind::<Text, _>(cur_name)
+                    .execute(x)?;
+                sql_query("DELETE FROM recipe_tag WHERE tag_id IN (SELECT tag_id FROM tag WHERE tag_name=?);")
+                    .bind::<Text, _>(cur_name)
+                    .execute(x)?;
+                sql_query("DELETE FROM tag WHERE tag_name=?;")
+                    .bind::<Text, _>(cur_name)
+                    .execute(x)?;
+            } else {
+                sql_query("INSERT OR IGNORE INTO tag(tag_name) VALUES (?);")
+                    .bind::<Text, _>(cur_name)
+                    .execute(x)?;
+                sql_query("INSERT OR IGNORE INTO recipe_tag(recipe_id, tag_id)
+SELECT ri.recipe_id, t.tag_id FROM recipe_ingredient ri INNER JOIN ingredient i ON i.id = ri.ingredient_id INNER JOIN tag t ON t.tag_name = lower(i.name) WHERE lower(i.name)=?;")
+                    .bind::<Text, _>(cur_name)
+                    .execute(x)?;
+                sql_query("DELETE FROM recipe_ingredient WHERE ingredient_id IN (SELECT id FROM ingredient WHERE lower(name)=?);")
+                    .bind::<Text, _>(cur_name)
+                    .execute(x)?;
+                sql_query("DELETE FROM ingredient WHERE lower(name)=?;")
+                    .bind::<Text, _>(cur_name)
+                    .execute(x)?;
+            }
+        }
+        Ok(())
+    }).unwrap();
+    update_index_for(&search_state, con, &affected);
+
+    Redirect::to("/admin/tags").into_response()
+}
+
+/// Empty values fall back to what the ingredients say.
+async fn post_diet_override(State(search_state): State<SearchState>, session: WritableSession, Path(path): Path<i32>, Form(form): Form<HashMap<String, String>>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::recipe_diet_override::dsl::*;
+        diesel::delete(recipe_diet_override.filter(recipe_id.eq(path))).execute(x)?;
+        let rows: Vec<(i32, String, bool)> = EDiet::get_diets().into_iter()
+            .filter_map(|cur_diet| form.get(cur_diet.code())
+                .filter(|y| !y.is_empty())
+                .map(|y| (path, cur_diet.code().to_string(), y == "1")))
+            .collect();
+        for (cur_recipe_id, cur_diet, cur_is_diet) in rows {
+            diesel::insert_into(recipe_diet_override)
+                .values((recipe_id.eq(cur_recipe_id), diet.eq(cur_diet), is_diet.eq(cur_is_diet)))
+                .execute(x)?;
+        }
+        Ok(())
+    }).unwrap();
+    update_index_for(&search_state, con, &[path]);
+
+    Redirect::to(format!("/recipe/detail/{}", path).as_str()).into_response()
+}
+
+/// The primary season is set in the edit form, here it only gets its months.
+async fn post_recipe_seasons(State(search_state): State<SearchState>, session: WritableSession, Path(path): Path<i32>, Form(form): Form<HashMap<String, String>>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let cur_primary_season: Option<i32> = {
+        use recipemanagement::schema::recipe::dsl::*;
+        recipe.filter(recipe_id.eq(path)).select(primary_season).first::<i32>(con).ok()
+    };
+    if cur_primary_season.is_none() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let month = |key: String| form.get(&key).and_then(|x| x.parse::<i32>().ok());
+    let rows: Vec<(i32, Option<MonthRange>)> = query_seasons(con).into_iter()
+        .map(|x| x.season_id)
+        .filter(|x| Some(*x) == cur_primary_season || form.contains_key(&format!("season{}", x)))
+        .map(|x| (x, MonthRange::new(month(format!("start{}", x)), month(format!("end{}", x)))))
+        .filter(|x| Some(x.0) != cur_primary_season || x.1.is_some())
+        .collect();
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::recipe_season::dsl::*;
+        diesel::delete(recipe_season.filter(recipe_id.eq(path))).execute(x)?;
+        for (cur_season_id, months) in rows {
+            diesel::insert_into(recipe_season)
+                .values((recipe_id.eq(path), season_id.eq(cur_season_id),
+                         start_month.eq(months.map(|y| y.start as i32)), end_month.eq(months.map(|y| y.end as i32))))
+                .execute(x)?;
+        }
+        Ok(())
+    }).unwrap();
+    if let Some(enriched_recipe) = query_all_recipes(con).into_iter().find(|x| x.recipe.recipe_id == Some(path)) {
+        update_index(&search_state, enriched_recipe);
+    }
+
+    Redirect::to(format!("/recipe/detail/{}", path).as_str()).into_response()
+}
+
+/// Servings and gram amounts for the nutrition estimate, empty fields clear them.
+async fn post_recipe_quantities(session: WritableSession, Path(path): Path<i32>, Form(form): Form<HashMap<String, String>>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let own_ingredients: Vec<i32> = {
+        use recipemanagement::schema::recipe_ingredient::dsl::*;
+        recipe_ingredient.filter(recipe_id.eq(path)).select(ingredient_id).load::<i32>(con).unwrap()
+    };
+    con.transaction::<_, Error, _>(|x| {
+        {
+            use recipemanagement::schema::recipe::dsl::*;
+            let cur_servings = form.get("servings").and_then(|y| y.trim().parse::<i32>().ok()).filter(|y| *y > 0);
+            diesel::update(recipe.filter(recipe_id.eq(path))).set(servings.eq(cur_servings)).execute(x)?;
+        }
+        use recipemanagement::schema::recipe_ingredient::dsl::*;
+        for cur_ingredient_id in own_ingredients {
+            let grams = form.get(format!("grams{}", cur_ingredient_id).as_str())
+                .and_then(|y| y.trim().replace(',', ".").parse::<f32>().ok())
+                .filter(|y| *y >= 0.0);
+            diesel::update(recipe_ingredient.filter(recipe_id.eq(path)).filter(ingredient_id.eq(cur_ingredient_id)))
+                .set(amount_grams.eq(grams))
+                .execute(x)?;
+        }
+        Ok(())
+    }).unwrap();
+
+    Redirect::to(format!("/recipe/detail/{}", path).as_str()).into_response()
+}
+
+/// Only the equipment of the recipe itself, what its components need is added when reading.
+async fn post_recipe_equipment(session: WritableSession, Path(path): Path<i32>, Form(form): Form<HashMap<String, String>>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !query_recipe_names(con).contains_key(&path) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let selected: Vec<i32> = query_equipment(con).into_iter()
+        .filter_map(|x| x.equipment_id)
+        .filter(|x| form.contains_key(&format!("equipment{}", x)))
+        .collect();
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::recipe_equipment::dsl::*;
+        diesel::delete(recipe_equipment.filter(recipe_id.eq(path))).execute(x)?;
+        for cur_equipment_id in selected {
+            diesel::insert_into(recipe_equipment)
+                .values((recipe_id.eq(path), equipment_id.eq(cur_equipment_id)))
+                .execute(x)?;
+        }
+        Ok(())
+    }).unwrap();
+
+    Redirect::to(format!("/recipe/detail/{}", path).as_str()).into_response()
+}
+
+#[derive(Deserialize)]
+struct PostRecipeLink {
+    link_type: String,
+    linked_recipe_id: i32,
+    /// Set when removing a link that starts at the linked recipe.
+    incoming: Option<String>,
+    delete: Option<String>,
+}
+
+/// Deleting removes a served with link in either direction and other links only in the given one, components that would make a recipe contain itself are refused.
+async fn post_recipe_link(State(search_state): State<SearchState>, session: WritableSession, Path(path): Path<i32>, Form(form): Form<PostRecipeLink>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let Some(cur_link_type) = ELinkType::from_code(form.link_type.as_str()) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    if form.linked_recipe_id == path {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    let con = &mut database::establish_connection();
+    let names = query_recipe_names(con);
+    if !names.contains_key(&path) || !names.contains_key(&form.linked_recipe_id) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    if cur_link_type == ELinkType::Component && form.delete.is_none() && creates_cycle(path, form.linked_recipe_id, &query_recipe_links(con)) {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    use recipemanagement::schema::recipe_link::dsl::*;
+    let (from_id, to_id) = if form.incoming.is_some() { (form.linked_recipe_id, path) } else { (path, form.linked_recipe_id) };
+    if form.delete.is_some() {
+        let outgoing = recipe_id.eq(from_id).and(linked_recipe_id.eq(to_id));
+        let reverse = recipe_id.eq(to_id).and(linked_recipe_id.eq(from_id));
+        let rows = recipe_link.filter(link_type.eq(cur_link_type.code()));
+        if cur_link_type == ELinkType::ServeWith {
+            diesel::delete(rows.filter(outgoing.or(reverse))).execute(con).unwrap();
+        } else {
+            diesel::delete(rows.filter(outgoing)).execute(con).unwrap();
+        }
+    } else {
+        // serving a with b is the same as serving b with a
+        let reverse_exists = cur_link_type == ELinkType::ServeWith && select(exists(
+            recipe_link.filter(recipe_id.eq(form.linked_recipe_id)).filter(linked_recipe_id.eq(path)).filter(link_type.eq(cur_link_type.code()))
+        )).get_result::<bool>(con).unwrap();
+        if !reverse_exists {
+            diesel::insert_or_ignore_into(recipe_link)
+                .values((recipe_id.eq(path), linked_recipe_id.eq(form.linked_recipe_id), link_type.eq(cur_link_type.code())))
+                .execute(con)
+                .unwrap();
+        }
+    }
+    if cur_link_type == ELinkType::Component {
+        update_index_with_users(&search_state, con, from_id);
+    }
+
+    Redirect::to(format!("/recipe/detail/{}", path).as_str()).into_response()
+}
+
+async fn season_admin(session: WritableSession) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    let used: HashSet<i32> = {
+        use recipemanagement::schema::recipe::dsl::*;
+        recipe.select(primary_season).distinct().load::<i32>(con).unwrap().into_iter().collect()
+    };
+    let seasons: Vec<(Season, i32, bool)> = {
+        use recipemanagement::schema::season::dsl::*;
+        season.order((position, season_id)).load::<QSeason>(con).unwrap()
+    }.iter()
+        .map(|x| (Season::from(x), x.position, used.contains(&x.season_id.unwrap())))
+        .collect();
+    let build_version = env!("VERGEN_GIT_SHA");
+
+    Html(SeasonAdmin {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: "Seasons",
+        seasons,
+        months: months(),
+        user_id: maybe_user_id,
+        build_version,
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+#[derive(Deserialize)]
+struct PostSeason {
+    season_id: Option<i32>,
+    name: String,
+    start_month: Option<String>,
+    end_month: Option<String>,
+    position: Option<i32>,
+    all_year: Option<String>,
+    delete: Option<String>,
+}
+
+/// Without an id a new season is added, seasons still used as a primary season cannot be deleted.
+async fn post_season(State(search_state): State<SearchState>, session: WritableSession, Form(form): Form<PostSeason>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let month = |x: &Option<String>| x.as_ref().and_then(|y| y.parse::<i32>().ok());
+    let months = MonthRange::new(month(&form.start_month), month(&form.end_month));
+    let cur_name = form.name.trim().to_string();
+    let (in_use, name_taken): (bool, bool) = {
+        use recipemanagement::schema::season::dsl::*;
+        let cur_season_id = form.season_id.unwrap_or(-1);
+        (diesel::select(exists(schema::recipe::table.filter(schema::recipe::primary_season.eq(cur_season_id)))).get_result(con).unwrap(),
+         diesel::select(exists(season.filter(tag_name.eq(&cur_name)).filter(season_id.ne(cur_season_id)))).get_result(con).unwrap())
+    };
+    if form.delete.is_some() && in_use || form.delete.is_none() && name_taken {
+        return StatusCode::CONFLICT.into_response();
+    }
+    if cur_name.is_empty() && form.delete.is_none() || form.delete.is_some() && form.season_id.is_none() {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::season::dsl::*;
+        let values = (tag_name.eq(&cur_name), start_month.eq(months.map(|y| y.start as i32)),
+                      end_month.eq(months.map(|y| y.end as i32)), position.eq(form.position.unwrap_or(0)),
+                      all_year.eq(form.all_year.is_some()));
+        match (form.season_id, form.delete.is_some()) {
+            (Some(cur_season_id), true) => {
+                diesel::delete(schema::recipe_season::table.filter(schema::recipe_season::season_id.eq(cur_season_id))).execute(x)?;
+                diesel::delete(season.filter(season_id.eq(cur_season_id))).execute(x)?;
+            }
+            (Some(cur_season_id), false) => {
+                diesel::update(season.filter(season_id.eq(cur_season_id))).set(values).execute(x)?;
+            }
+            (None, false) => {
+                diesel::insert_into(season).values(values).execute(x)?;
+            }
+            (None, true) => unreachable!(),
+        }
+        Ok(())
+    }).unwrap();
+    nuke_and_rebuild_index(&search_state);
+
+    Redirect::to("/admin/seasons").into_response()
+}
+
+async fn equipment_admin(session: WritableSession) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    let counts: HashMap<i32, usize> = {
+        use recipemanagement::schema::recipe_equipment::dsl::*;
+        recipe_equipment.select(equipment_id).load::<i32>(con).unwrap().into_iter().counts()
+    };
+    let equipment: Vec<(Equipment, usize)> = query_equipment(con).into_iter()
+        .map(|x| {
+            let count = counts.get(&x.equipment_id.unwrap()).copied().unwrap_or(0);
+            (x, count)
+        })
+        .collect();
+
+    Html(EquipmentAdmin {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: "Equipment",
+        equipment,
+        user_id: maybe_user_id,
+        build_version: env!("VERGEN_GIT_SHA"),
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+#[derive(Deserialize)]
+struct PostEquipment {
+    equipment_id: Option<i32>,
+    name: String,
+    delete: Option<String>,
+}
+
+/// Without an id new equipment is added, equipment recipes still need cannot be deleted.
+async fn post_equipment(session: WritableSession, Form(form): Form<PostEquipment>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let cur_name = form.name.trim().to_string();
+    let (in_use, name_taken): (bool, bool) = {
+        use recipemanagement::schema::equipment::dsl::*;
+        let cur_equipment_id = form.equipment_id.unwrap_or(-1);
+        (diesel::select(exists(schema::recipe_equipment::table.filter(schema::recipe_equipment::equipment_id.eq(cur_equipment_id)))).get_result(con).unwrap(),
+         diesel::select(exists(equipment.filter(name.eq(&cur_name)).filter(equipment_id.ne(cur_equipment_id)))).get_result(con).unwrap())
+    };
+    if form.delete.is_some() && in_use || form.delete.is_none() && name_taken {
+        return StatusCode::CONFLICT.into_response();
+    }
+    if cur_name.is_empty() && form.delete.is_none() || form.delete.is_some() && form.equipment_id.is_none() {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::equipment::dsl::*;
+        match (form.equipment_id, form.delete.is_some()) {
+            (Some(cur_equipment_id), true) => {
+                diesel::delete(equipment.filter(equipment_id.eq(cur_equipment_id))).execute(x)?;
+            }
+            (Some(cur_equipment_id), false) => {
+                diesel::update(equipment.filter(equipment_id.eq(cur_equipment_id))).set(name.eq(&cur_name)).execute(x)?;
+            }
+            (None, false) => {
+                diesel::insert_into(equipment).values(name.eq(&cur_name)).execute(x)?;
+            }
+            (None, true) => unreachable!(),
+        }
+        Ok(())
+    }).unwrap();
+
+    Redirect::to("/admin/equipment").into_response()
+}
+
+async fn price_admin(session: WritableSession) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    let today = Local::now().date_naive();
+    let mut current_prices = query_ingredient_prices(con);
+    let currency: String = {
+        use recipemanagement::schema::ingredient_price::dsl::*;
+        ingredient_price.order(price_id.desc()).select(currency).first::<String>(con).unwrap_or("EUR".to_string())
+    };
+    use recipemanagement::schema::ingredient::dsl::*;
+    let prices: Vec<(Ingredient, Option<CurrentPrice>)> = ingredient.load::<Ingredient>(con)
+        .unwrap()
+        .into_iter()
+        .filter(|x| x.name.is_some())
+        .sorted_by_key(|x| x.name.as_ref().unwrap().to_lowercase())
+        .map(|x| {
+            let current = current_prices.remove(&x.id.unwrap()).map(|y| {
+                let description = describe_price(&y);
+                let stale = is_stale(&y, today);
+                (y, description, stale)
+            });
+            (x, current)
+        })
+        .collect();
+
+    Html(PriceAdmin {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: "Ingredient prices",
+        units: EPriceUnit::get_units(),
+        currency,
+        today: today.to_string(),
+        prices,
+        user_id: maybe_user_id,
+        build_version: env!("VERGEN_GIT_SHA"),
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+#[derive(Deserialize)]
+struct PostIngredientPrice {
+    ingredient_id: i32,
+    price: Option<String>,
+    quantity: Option<String>,
+    unit: Option<String>,
+    piece_grams: Option<String>,
+    currency: Option<String>,
+    priced_on: Option<String>,
+    delete: Option<String>,
+}
+
+/// Adds a price for an ingredient, older ones are kept but no longer used. Deleting clears all its prices.
+async fn post_ingredient_price(session: WritableSession, Form(form): Form<PostIngredientPrice>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let known_ingredient = {
+        use recipemanagement::schema::ingredient::dsl::*;
+        diesel::select(exists(ingredient.filter(id.eq(form.ingredient_id)))).get_result::<bool>(con).unwrap()
+    };
+    if !known_ingredient {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    use recipemanagement::schema::ingredient_price::dsl::*;
+    if form.delete.is_some() {
+        diesel::delete(ingredient_price.filter(ingredient_id.eq(form.ingredient_id))).execute(con).unwrap();
+        return Redirect::to("/admin/prices").into_response();
+    }
+
+    let number = |value: &Option<String>| value.as_deref().and_then(|x| x.trim().replace(',', ".").parse::<f32>().ok());
+    let cur_price = number(&form.price).filter(|x| *x >= 0.0);
+    let cur_quantity = number(&form.quantity).filter(|x| *x > 0.0);
+    let cur_unit = form.unit.as_deref().and_then(EPriceUnit::from_code);
+    let cur_piece_grams = number(&form.piece_grams).filter(|x| *x > 0.0);
+    let cur_currency = form.currency.as_deref().unwrap_or_default().trim().to_uppercase();
+    let cur_priced_on = form.priced_on.as_deref().and_then(|x| NaiveDate::parse_from_str(x.trim(), "%Y-%m-%d").ok());
+    let (Some(cur_price), Some(cur_quantity), Some(cur_unit), Some(cur_priced_on)) = (cur_price, cur_quantity, cur_unit, cur_priced_on) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    if cur_unit.grams(cur_piece_grams).is_none() || cur_currency.len() != 3 || !cur_currency.chars().all(|x| x.is_ascii_alphabetic()) {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    diesel::insert_into(ingredient_price)
+        .values((
+            ingredient_id.eq(form.ingredient_id),
+            price.eq(cur_price),
+            quantity.eq(cur_quantity),
+            unit.eq(cur_unit.code()),
+            piece_grams.eq(cur_piece_grams.filter(|_| cur_unit == EPriceUnit::Piece)),
+            currency.eq(&cur_currency),
+            priced_on.eq(cur_priced_on.to_string()),
+        ))
+        .execute(con)
+        .unwrap();
+
+    Redirect::to("/admin/prices").into_response()
+}
+
+async fn ingredient_admin(session: WritableSession) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    let mut attributes = query_ingredient_attributes(con);
+    let mut nutrients = query_ingredient_nutrients(con);
+    let foods: Vec<String> = {
+        use recipemanagement::schema::nutrient::dsl::*;
+        nutrient.order((source, food_name)).load::<Nutrient>(con).unwrap().iter().map(food_label).collect()
+    };
+    use recipemanagement::schema::ingredient::dsl::*;
+    let ingredients: Vec<(Ingredient, HashSet<EIngredientAttribute>, Option<String>)> = ingredient.load::<Ingredient>(con)
+        .unwrap()
+        .into_iter()
+        .filter(|x| x.name.is_some())
+        .sorted_by_key(|x| x.name.as_ref().unwrap().to_lowercase())
+        .map(|x| {
+            let ingredient_attributes = attributes.remove(&x.id.unwrap()).unwrap_or_default();
+            let food = nutrients.remove(&x.id.unwrap()).map(|y| food_label(&y));
+            (x, ingredient_attributes, food)
+        })
+        .collect();
+    let build_version = env!("VERGEN_GIT_SHA");
+
+    Html(IngredientAdmin {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: "Ingredients",
+        attributes: EIngredientAttribute::get_attributes(),
+        ingredients,
+        foods,
+        user_id: maybe_user_id,
+        build_version,
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+async fn post_ingredient_attributes(State(search_state): State<SearchState>, session: WritableSession, Form(form): Form<HashMap<String, String>>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let Some(cur_ingredient_id) = form.get("ingredient_id").and_then(|x| x.parse::<i32>().ok()) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::ingredient_attribute::dsl::*;
+        diesel::delete(ingredient_attribute.filter(ingredient_id.eq(cur_ingredient_id))).execute(x)?;
+        for cur_attribute in EIngredientAttribute::get_attributes().into_iter().filter(|y| form.contains_key(y.code())) {
+            diesel::insert_into(ingredient_attribute)
+                .values((ingredient_id.eq(cur_ingredient_id), attribute.eq(cur_attribute.code())))
+                .execute(x)?;
+        }
+        // saving a row is what tells a plain ingredient from one nobody has looked at
+        diesel::update(schema::ingredient::table.filter(schema::ingredient::id.eq(cur_ingredient_id)))
+            .set(schema::ingredient::classified.eq(true))
+            .execute(x)?;
+        Ok(())
+    }).unwrap();
+    // an empty food removes the mapping, text that is no food of the nutrient table leaves it alone
+    if let Some(food) = form.get("food") {
+        use recipemanagement::schema::ingredient_nutrient::dsl::*;
+        let found: Option<i32> = parse_food_label(food).and_then(|(cur_source, cur_code)| {
+            use recipemanagement::schema::nutrient::dsl::*;
+            nutrient.filter(source.eq(cur_source)).filter(source_code.eq(cur_code)).select(nutrient_id).first::<Option<i32>>(con).ok().flatten()
+        });
+        if food.trim().is_empty() {
+            diesel::delete(ingredient_nutrient.filter(ingredient_id.eq(cur_ingredient_id))).execute(con).unwrap();
+        } else if let Some(found) = found {
+            diesel::replace_into(ingredient_nutrient)
+                .values((ingredient_id.eq(cur_ingredient_id), nutrient_id.eq(found)))
+                .execute(con)
+                .unwrap();
+        }
+    }
+    // recipes using it as part of a sub-recipe derive their diets from it as well
+    let affected: Vec<i32> = query_recipes_to_ingredient_ids(con).into_iter()
+        .filter(|(_, ingredient_ids)| ingredient_ids.contains(&cur_ingredient_id))
+        .map(|(cur_recipe_id, _)| cur_recipe_id)
+        .collect();
+    update_index_for(&search_state, con, &affected);
+
+    Redirect::to("/admin/ingredients").into_response()
+}
+
+/// Rating or cooking a recipe implies having tried it.
+fn mark_tried(con: &mut SqliteConnection, cur_user_id: i32, cur_recipe_id: i32) -> Result<(), Error> {
+    sql_query("INSERT OR IGNORE INTO tried(user_id, recipe_id) VALUES (?, ?);")
+        .bind::<Integer, _>(cur_user_id)
+        .bind::<Integer, _>(cur_recipe_id)
+        .execute(con)?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct PostRating {
+    rating: String,
+    cook_again: Option<i32>,
+}
+
+async fn post_rating(session: WritableSession, Path(path): Path<i32>, Form(form): Form<PostRating>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let stars = form.rating.parse::<i32>().ok().filter(|x| (1..=5).contains(x));
+    let again = form.cook_again.is_some();
+    let con = &mut database::establish_connection();
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::recipe_rating::dsl::*;
+        if stars.is_none() && !again {
+            diesel::delete(recipe_rating.filter(user_id.eq(maybe_user_id.unwrap())).filter(recipe_id.eq(path))).execute(x)?;
+            return Ok(());
+        }
+        diesel::insert_into(recipe_rating)
+            .values((user_id.eq(maybe_user_id.unwrap()), recipe_id.eq(path), rating.eq(stars), cook_again.eq(again)))
+            .on_conflict((user_id, recipe_id))
+            .do_update()
+            .set((rating.eq(stars), cook_again.eq(again)))
+            .execute(x)?;
+        mark_tried(x, maybe_user_id.unwrap(), path)
+    }).unwrap();
+
+    Redirect::to(format!("/recipe/detail/{}", path).as_str()).into_response()
+}
+
+#[derive(Default)]
+struct PostCooked {
+    cooked_on: String,
+    notes: Option<String>,
+    servings: Option<i32>,
+    photo: Option<String>,
+}
+
+async fn post_cooked(session: WritableSession, Path(path): Path<i32>, mut multipart: Multipart) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let mut form = PostCooked::default();
+    while let Ok(Some(field)) = multipart.next_field().await {
+        let field_name = field.name().unwrap_or_default().to_string();
+        if field_name == "photo" {
+            let file_name = field.file_name().unwrap_or_default().to_string();
+            let Ok(content) = field.bytes().await else {
+                return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+            };
+            if content.is_empty() {
+                continue;
+            }
+            match store_image(file_name.as_str(), &content) {
+                Ok(Some(name)) => form.photo = Some(name),
+                Ok(None) => return StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response(),
+                Err(e) => {
+                    error!("Could not store upload: {}", e);
+                    return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                }
+            }
+            continue;
+        }
+        let value = field.text().await.unwrap_or_default().trim().to_string();
+        match field_name.as_str() {
+            "cooked_on" => form.cooked_on = value,
+            "notes" => form.notes = Some(value).filter(|x| !x.is_empty()),
+            "servings" => form.servings = value.parse::<i32>().ok().filter(|x| *x > 0),
+            _ => {}
+        }
+    }
+    let con = &mut database::establish_connection();
+    let Ok(date) = NaiveDate::parse_from_str(form.cooked_on.as_str(), "%Y-%m-%d") else {
+        if let Some(cur_photo) = form.photo.as_ref() {
+            remove_unused_image(con, cur_photo);
+        }
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::cooked::dsl::*;
+        diesel::insert_into(cooked)
+            .values((user_id.eq(maybe_user_id.unwrap()), recipe_id.eq(path), cooked_on.eq(date.to_string()),
+                     notes.eq(form.notes), servings.eq(form.servings), photo.eq(form.photo)))
+            .execute(x)?;
+        mark_tried(x, maybe_user_id.unwrap(), path)
+    }).unwrap();
+
+    Redirect::to(format!("/recipe/detail/{}", path).as_str()).into_response()
+}
+
+/// Images are stored by content hash, another recipe or log entry may still point to the same file.
+fn remove_unused_image(con: &mut SqliteConnection, name: &str) {
+    use recipemanagement::schema::cooked::dsl::*;
+    use recipemanagement::schema::recipe_image::dsl::*;
+    let still_used = select(exists(cooked.filter(photo.eq(name)))).get_result::<bool>(con).unwrap()
+        || select(exists(recipe_image.filter(file_name.eq(name)))).get_result::<bool>(con).unwrap();
+    if !still_used {
+        remove_image(name).unwrap_or_else(|e| warn!("Could not remove upload {}: {}", name, e));
+    }
+}
+
+async fn delete_cooked(session: WritableSession, Path(path): Path<i32>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    use recipemanagement::schema::cooked::dsl::*;
+    let Ok(entry) = cooked.filter(cooked_id.eq(path)).filter(user_id.eq(maybe_user_id.unwrap())).first::<Cooked>(con) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    diesel::delete(cooked.filter(cooked_id.eq(path))).execute(con).unwrap();
+    if let Some(cur_photo) = entry.photo.as_ref() {
+        remove_unused_image(con, cur_photo);
+    }
+
+    Redirect::to(format!("/recipe/detail/{}", entry.recipe_id).as_str()).into_response()
+}
+
+async fn top_rated(session: WritableSession) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    let current_month = Local::now().month();
+    let seasons = query_seasons(con);
+    let current_season = Some(seasons_in_month(&seasons, current_month).iter().join(" / "))
+        .filter(|x| !x.is_empty())
+        .unwrap_or(month_name(current_month).to_string());
+    let ratings = query_rating_summaries(con);
+    let recipe_seasons = query_recipe_seasons(con);
+    use recipemanagement::schema::recipe::dsl::*;
+    let entries: Vec<(FullRecipe, RatingSummary)> = recipe.load::<FullRecipe>(con)
+        .unwrap()
+        .into_iter()
+        .filter(|x| recipe_seasons.get(&x.recipe_id.unwrap()).is_some_and(|y| y.iter().any(|z| z.in_season(current_month, &seasons))))
+        .filter_map(|x| {
+            let summary = ratings.get(&x.recipe_id.unwrap()).filter(|y| y.average.is_some())?.clone();
+            Some((x, summary))
+        })
+        .sorted_by(|a, b| rating_order(Some(&a.1), Some(&b.1)))
+        .take(20)
+        .collect();
+    let own_ratings: HashMap<i32, RecipeRating> = {
+        use recipemanagement::schema::recipe_rating::dsl::*;
+        recipe_rating.filter(user_id.eq(maybe_user_id.unwrap()))
+            .load::<RecipeRating>(con)
+            .unwrap()
+            .into_iter()
+            .map(|x| (x.recipe_id, x))
+            .collect()
+    };
+    let last_cooked: HashMap<i32, String> = {
+        use recipemanagement::schema::cooked::dsl::*;
+        cooked.filter(user_id.eq(maybe_user_id.unwrap()))
+            .load::<Cooked>(con)
+            .unwrap()
+            .into_iter()
+            .map(|x| (x.recipe_id, x.cooked_on))
+            .into_grouping_map()
+            .max()
+    };
+    let build_version = env!("VERGEN_GIT_SHA");
+
+    Html(TopRated {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: "Top rated",
+        season: current_season,
+        entries,
+        own_ratings,
+        last_cooked,
+        user_id: maybe_user_id,
+        build_version,
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+async fn in_season_now(session: WritableSession) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    let config = ProduceConfig::from_env();
+    let current_month = Local::now().month();
+    let in_season_ids = query_in_season_ingredients(con, &config, current_month);
+    let ingredient_names: HashMap<i32, String> = {
+        use recipemanagement::schema::ingredient::dsl::*;
+        ingredient.load::<Ingredient>(con)
+            .unwrap()
+            .into_iter()
+            .filter_map(|x| Some((x.id?, x.name?)))
+            .collect()
+    };
+    let recipes_to_ingredients = query_recipes_to_ingredient_ids(con);
+    let recipes: HashMap<i32, FullRecipe> = {
+        use recipemanagement::schema::recipe::dsl::*;
+        recipe.load::<FullRecipe>(con).unwrap().into_iter().map(|x| (x.recipe_id.unwrap(), x)).collect()
+    };
+    let entries: Vec<(FullRecipe, Vec<String>, usize)> = rank_by_season(&recipes_to_ingredients, &in_season_ids).into_iter()
+        .filter_map(|x| {
+            let names = x.in_season.iter().filter_map(|y| ingredient_names.get(y).cloned()).sorted().collect();
+            Some((recipes.get(&x.recipe_id)?.clone(), names, x.total))
+        })
+        .take(30)
+        .collect();
+    let produce: Vec<String> = in_season_ids.iter()
+        .filter_map(|x| ingredient_names.get(x).cloned())
+        .sorted_by_key(|x| x.to_lowercase())
+        .collect();
+    let build_version = env!("VERGEN_GIT_SHA");
+
+    Html(InSeason {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: "In season now",
+        month: month_name(current_month),
+        season: seasons_in_month(&query_seasons(con), current_month).iter().join(" / "),
+        config,
+        produce,
+        entries,
+        user_id: maybe_user_id,
+        build_version,
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+#[derive(Deserialize)]
+struct ProduceRegion {
+    region: Option<String>,
+}
+
+async fn produce_admin(session: WritableSession, Query(params): Query<ProduceRegion>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    let config = ProduceConfig::from_env();
+    let cur_region = params.region.map(|x| x.trim().to_lowercase()).filter(|x| !x.is_empty()).unwrap_or(config.region.clone());
+    let mut calendar = query_produce_calendar(con, cur_region.as_str());
+    let mut regions = query_produce_regions(con);
+    for configured in [config.region.clone(), cur_region.clone()] {
+        if !regions.contains(&configured) {
+            regions.push(configured);
+        }
+    }
+    regions.sort();
+    use recipemanagement::schema::ingredient::dsl::*;
+    let ingredients: Vec<(Ingredient, HashSet<u32>)> = ingredient.load::<Ingredient>(con)
+        .unwrap()
+        .into_iter()
+        .filter(|x| x.name.is_some())
+        .sorted_by_key(|x| x.name.as_ref().unwrap().to_lowercase())
+        .map(|x| {
+            let ingredient_months = calendar.remove(&x.id.unwrap()).unwrap_or_default();
+            (x, ingredient_months)
+        })
+        .collect();
+    let build_version = env!("VERGEN_GIT_SHA");
+
+    Html(ProduceAdmin {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: "Produce calendar",
+        region: cur_region,
+        regions,
+        config,
+        months: months(),
+        ingredients,
+        user_id: maybe_user_id,
+        build_version,
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+async fn post_produce_months(session: WritableSession, Form(form): Form<HashMap<String, String>>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    if !user_is_admin(maybe_user_id.unwrap(), con) {
+        return Redirect::to("/").into_response();
+    }
+    let cur_ingredient_id = form.get("ingredient_id").and_then(|x| x.parse::<i32>().ok());
+    let cur_region = form.get("region").map(|x| x.trim().to_lowercase()).filter(|x| !x.is_empty());
+    let (Some(cur_ingredient_id), Some(cur_region)) = (cur_ingredient_id, cur_region) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::produce_month::dsl::*;
+        diesel::delete(produce_month.filter(ingredient_id.eq(cur_ingredient_id)).filter(region.eq(&cur_region))).execute(x)?;
+        for cur_month in (1..=12).filter(|y| form.contains_key(&format!("m{}", y))) {
+            diesel::insert_into(produce_month)
+                .values((ingredient_id.eq(cur_ingredient_id), region.eq(&cur_region), month.eq(cur_month)))
+                .execute(x)?;
+        }
+        Ok(())
+    }).unwrap();
+
+    Redirect::to(format!("/admin/produce?{}", serde_urlencoded::to_string([("region", cur_region)]).unwrap()).as_str()).into_response()
+}
+
+async fn serve_upload(session: WritableSession, Path(path): Path<String>) -> Response {
+    if get_user_id(session).is_none() {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    if let Some((hash, _)) = parse_thumbnail_name(path.as_str()) {
+        // uploads from before thumbnails existed get them on first view
+        if !upload_dir().join(&path).exists() {
+            let Some(original) = find_original(hash.as_str()) else { return StatusCode::NOT_FOUND.into_response() };
+            generate_thumbnails(original.as_str()).unwrap_or_else(|e| warn!("Could not create thumbnails for {}: {}", original, e));
+        }
+    } else if !is_stored_name(path.as_str()) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    match tokio::fs::read(upload_dir().join(&path)).await {
+        Ok(content) => ([(header::CONTENT_TYPE, content_type(path.as_str()))], content).into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn cooking_history(session: WritableSession) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
+    let id_to_recipe: HashMap<i32, FullRecipe> = {
+        use recipemanagement::schema::recipe::dsl::*;
+        recipe.load::<FullRecipe>(con)
+            .unwrap()
+            .into_iter()
+            .map(|x| (x.recipe_id.unwrap(), x))
+            .collect()
+    };
+    use recipemanagement::schema::cooked::dsl::*;
+    let entries: Vec<(String, Vec<(Cooked, FullRecipe)>)> = cooked.filter(user_id.eq(maybe_user_id.unwrap()))
+        .order((cooked_on.desc(), cooked_id.desc()))
+        .load::<Cooked>(con)
+        .unwrap()
+        .into_iter()
+        .filter_map(|x| {
+            let cur_recipe = id_to_recipe.get(&x.recipe_id)?.clone();
+            Some((x, cur_recipe))
+        })
+        .group_by(|x| x.0.cooked_on.chars().take(7).collect::<String>())
+        .into_iter()
+        .map(|(month, month_entries)| (month, month_entries.collect()))
+        .collect();
+    let build_version = env!("VERGEN_GIT_SHA");
+
+    Html(History {
+        courses: &courses,
+        saved_searches: query_saved_searches(maybe_user_id, con),
+        title: "History",
+        entries,
+        user_id: maybe_user_id,
+        build_version,
+        debug_compilation: cfg!(debug_assertions),
+    }.get()).into_response()
+}
+
+async fn post_recipe_images(session: WritableSession, Path(path): Path<i32>, mut multipart: Multipart) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let mut names: Vec<String> = vec![];
+    while let Ok(Some(field)) = multipart.next_field().await {
+        if field.name() != Some("images") {
+            continue;
+        }
+        let file_name = field.file_name().unwrap_or_default().to_string();
+        let Ok(content) = field.bytes().await else {
+            return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+        };
+        match store_image(file_name.as_str(), &content) {
+            Ok(Some(name)) => names.push(name),
+            Ok(None) => warn!("Skipping upload {}, not a supported image", file_name),
+            Err(e) => {
+                error!("Could not store upload: {}", e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        }
+    }
+    if names.is_empty() {
+        return StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response();
+    }
+    let con = &mut database::establish_connection();
+    con.transaction::<_, Error, _>(|x| {
+        use recipemanagement::schema::recipe_image::dsl::*;
+        let next_position = recipe_image.filter(recipe_id.eq(path))
+            .select(max(position))
+            .first::<Option<i32>>(x)?
+            .map(|y| y + 1)
+            .unwrap_or(0);
+        for (offset, name) in names.iter().enumerate() {
+            diesel::insert_or_ignore_into(recipe_image)
+                .values((recipe_id.eq(path), file_name.eq(name), position.eq(next_position + offset as i32)))
+                .execute(x)?;
+        }
+        Ok(())
+    }).unwrap();
+
+    Redirect::to(format!("/recipe/detail/{}", path).as_str()).into_response()
+}
+
+async fn set_cover_image(session: WritableSession, Path(path): Path<i32>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    use recipemanagement::schema::recipe_image::dsl::*;
+    let Ok(image) = recipe_image.filter(image_id.eq(path)).first::<RecipeImage>(con) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    con.transaction::<_, Error, _>(|x| {
+        diesel::update(recipe_image.filter(recipe_id.eq(image.recipe_id))).set(is_cover.eq(false)).execute(x)?;
+        diesel::update(recipe_image.filter(image_id.eq(path))).set(is_cover.eq(true)).execute(x)?;
+        Ok(())
+    }).unwrap();
+
+    Redirect::to(format!("/recipe/detail/{}", image.recipe_id).as_str()).into_response()
+}
+
+async fn delete_recipe_image(session: WritableSession, Path(path): Path<i32>) -> Response {
+    let maybe_user_id = get_user_id(session);
+    if maybe_user_id.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let con = &mut database::establish_connection();
+    let image = {
+        use recipemanagement::schema::recipe_image::dsl::*;
+        let Ok(image) = recipe_image.filter(image_id.eq(path)).first::<RecipeImage>(con) else {
+            return StatusCode::NOT_FOUND.into_response();
+        };
+        diesel::delete(recipe_image.filter(image_id.eq(path))).execute(con).unwrap();
+        image
+    };
+    remove_unused_image(con, image.file_name.as_str());
+
+    Redirect::to(format!("/recipe/detail/{}", image.recipe_id).as_str()).into_response()
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use crate::models::Comment;
+
+/// Depth-first order with the nesting depth, replies to missing comments are shown at the top level.
+pub fn thread_comments(comments: Vec<Comment>) -> Vec<(Comment, usize)> {
+    let ids: Vec<i32> = comments.iter().map(|x| x.comment_id).collect();
+    let mut children: HashMap<Option<i32>, Vec<Comment>> = HashMap::new();
+    for comment in comments {
+        let parent = comment.parent_id.filter(|x| ids.contains(x));
+        children.entry(parent).or_default().push(comment);
+    }
+    for replies in children.values_mut() {
+        replies.sort_by_key(|x| x.comment_id);
+    }
+    let mut res = vec![];
+    let mut stack: Vec<(Comment, usize)> = children.remove(&None).unwrap_or_default().into_iter().rev().map(|x| (x, 0)).collect();
+    while let Some((comment, depth)) = stack.pop() {
+        if let Some(replies) = children.remove(&Some(comment.comment_id)) {
+            stack.extend(replies.into_iter().rev().map(|x| (x, depth + 1)));
+        }
+        res.push((comment, depth));
+    }
+    res
+}
+
+/// The comment itself and every reply below it.
+pub fn comment_and_replies(comments: &[Comment], root_id: i32) -> Vec<i32> {
+    let mut res = vec![root_id];
+    let mut i = 0;
+    while i < res.len() {
+        let cur = res[i];
+        res.extend(comments.iter().filter(|x| x.parent_id == Some(cur)).map(|x| x.comment_id));
+        i += 1;
+    }
+    res
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::comments::{comment_and_replies, thread_comments};
+    use crate::models::Comment;
+
+    fn comment(comment_id: i32, parent_id: Option<i32>) -> Comment {
+        Comment { comment_id, user_id: 1, recipe_id: 1, content: "".to_string(), created_at: 0.0, parent_id, modified_at: None }
+    }
+
+    #[test]
+    fn replies_follow_their_parent() {
+        let comments = vec![comment(1, None), comment(2, None), comment(3, Some(1)), comment(4, Some(3)), comment(5, Some(1)), comment(6, Some(99))];
+        let res: Vec<(i32, usize)> = thread_comments(comments).into_iter().map(|x| (x.0.comment_id, x.1)).collect();
+        assert_eq!(vec![(1, 0), (3, 1), (4, 2), (5, 1), (2, 0), (6, 0)], res);
+    }
+
+    #[test]
+    fn deleting_takes_replies_along() {
+        let comments = vec![comment(1, None), comment(2, Some(1)), comment(3, Some(2)), comment(4, None)];
+        assert_eq!(vec![1, 2, 3], comment_and_replies(&comments, 1));
+        assert_eq!(vec![4], comment_and_replies(&comments, 4));
+    }
+}
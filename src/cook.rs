@@ -0,0 +1,60 @@
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepTimer {
+    pub label: String,
+    pub seconds: u32,
+}
+
+/// Durations like "20 minutes", "1 h" or "8-10 Minuten" in a step.
+/// Ranges start a timer for the lower bound, so there is time to check before it is done.
+pub fn find_timers(step: &str) -> Vec<StepTimer> {
+    let re = Regex::new(r"(?i)\b(\d+(?:[.,]\d+)?)(?:\s*(?:-|–|to|bis)\s*\d+(?:[.,]\d+)?)?\s*(hours?|hrs?|h|stunden?|std|heures?|ore|minutes?|mins?|minuten?|minuti|seconds?|secs?|sekunden?|secondes?|secondi)\b").unwrap();
+    re.captures_iter(step)
+        .filter_map(|x| {
+            let amount: f32 = x[1].replace(',', ".").parse().ok()?;
+            let unit = x[2].to_lowercase();
+            let factor = if unit.starts_with('h') || unit.starts_with("st") || unit == "ore" {
+                3600.0
+            } else if unit.starts_with("min") {
+                60.0
+            } else {
+                1.0
+            };
+            Some(StepTimer { label: x[0].to_string(), seconds: (amount * factor).round() as u32 })
+        })
+        .filter(|x| x.seconds > 0)
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::cook::{find_timers, StepTimer};
+
+    fn seconds(step: &str) -> Vec<u32> {
+        find_timers(step).into_iter().map(|x| x.seconds).collect()
+    }
+
+    #[test]
+    fn finds_durations() {
+        assert_eq!(vec![StepTimer { label: "20 minutes".to_string(), seconds: 1200 }], find_timers("Simmer for 20 minutes."));
+        assert_eq!(vec![5400], seconds("Roast 1.5 hours"));
+        assert_eq!(vec![3600, 1800], seconds("Rest 1 h, then bake 30 mins"));
+        assert_eq!(vec![45], seconds("Blend 45 sec"));
+    }
+
+    #[test]
+    fn ranges_and_languages() {
+        assert_eq!(vec![480], seconds("Add eggs and cook 8-10 minutes."));
+        assert_eq!(vec![900], seconds("15 bis 20 Minuten köcheln"));
+        assert_eq!(vec![7200], seconds("Cuocere 2 ore"));
+    }
+
+    #[test]
+    fn ignores_other_numbers() {
+        assert!(find_timers("Add 4 eggs and 200 g flour").is_empty());
+        assert!(find_timers("Heat to 180 degrees").is_empty());
+        assert!(find_timers("Serves 4 hungry people").is_empty());
+    }
+}
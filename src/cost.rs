@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::models::IngredientPrice;
+
+/// Prices recorded longer ago than this are flagged on the recipe.
+pub const STALE_AFTER_DAYS: i64 = 365;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EPriceUnit {
+    Kilogram,
+    Gram,
+    Litre,
+    Millilitre,
+    Piece,
+}
+
+impl EPriceUnit {
+    pub fn get_units() -> Vec<EPriceUnit> {
+        vec![EPriceUnit::Kilogram, EPriceUnit::Gram, EPriceUnit::Litre, EPriceUnit::Millilitre, EPriceUnit::Piece]
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            EPriceUnit::Kilogram => "kg",
+            EPriceUnit::Gram => "g",
+            EPriceUnit::Litre => "l",
+            EPriceUnit::Millilitre => "ml",
+            EPriceUnit::Piece => "piece",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<EPriceUnit> {
+        EPriceUnit::get_units().into_iter().find(|x| x.code() == code)
+    }
+
+    /// Weight of one unit, liquids are taken to weigh as much as water and pieces need their weight given.
+    pub fn grams(&self, piece_grams: Option<f32>) -> Option<f32> {
+        match self {
+            EPriceUnit::Kilogram | EPriceUnit::Litre => Some(1000.0),
+            EPriceUnit::Gram | EPriceUnit::Millilitre => Some(1.0),
+            EPriceUnit::Piece => piece_grams.filter(|x| *x > 0.0),
+        }
+    }
+}
+
+impl fmt::Display for EPriceUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// `None` for unknown units and pieces without a weight.
+pub fn price_per_gram(price: &IngredientPrice) -> Option<f32> {
+    let grams = EPriceUnit::from_code(price.unit.as_str())?.grams(price.piece_grams)? * price.quantity;
+    Some(price.price / grams).filter(|_| grams > 0.0)
+}
+
+pub fn is_stale(price: &IngredientPrice, today: NaiveDate) -> bool {
+    NaiveDate::parse_from_str(price.priced_on.as_str(), "%Y-%m-%d")
+        .map(|x| (today - x).num_days() > STALE_AFTER_DAYS)
+        .unwrap_or(true)
+}
+
+/// Like "2.50 CHF per kg" or "3.00 EUR per 6 pieces".
+pub fn describe_price(price: &IngredientPrice) -> String {
+    if price.quantity == 1.0 {
+        return format!("{:.2} {} per {}", price.price, price.currency, price.unit);
+    }
+    let unit = if price.unit == EPriceUnit::Piece.code() { "pieces" } else { price.unit.as_str() };
+    format!("{:.2} {} per {} {}", price.price, price.currency, price.quantity, unit)
+}
+
+/// The price in use for an ingredient with its description and whether it is stale.
+pub type CurrentPrice = (IngredientPrice, String, bool);
+
+/// An ingredient of a recipe as far as cost is concerned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostInput {
+    pub name: String,
+    pub amount_grams: Option<f32>,
+    pub price: Option<IngredientPrice>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostEstimate {
+    /// By currency, prices are never converted.
+    pub totals: BTreeMap<String, f32>,
+    pub servings: Option<i32>,
+    pub counted: usize,
+    pub unpriced: Vec<String>,
+    pub without_amount: Vec<String>,
+    pub stale: Vec<String>,
+}
+
+impl CostEstimate {
+    /// The whole recipe when the number of servings is unknown.
+    pub fn per_serving(&self) -> BTreeMap<String, f32> {
+        let servings = self.servings.unwrap_or(1).max(1) as f32;
+        self.totals.iter().map(|(currency, total)| (currency.clone(), total / servings)).collect()
+    }
+
+    /// For sorting and filtering, only when the servings are known and a single currency is involved.
+    pub fn comparable_per_serving(&self) -> Option<f32> {
+        self.servings?;
+        let per_serving = self.per_serving();
+        if per_serving.len() != 1 {
+            return None;
+        }
+        per_serving.values().next().copied()
+    }
+
+    /// Nothing could be counted, so there is no estimate to show.
+    pub fn is_empty(&self) -> bool {
+        self.counted == 0
+    }
+}
+
+/// Ingredients without a usable price or without an amount are left out and listed instead.
+pub fn estimate_cost(ingredients: &[CostInput], servings: Option<i32>, today: NaiveDate) -> CostEstimate {
+    let mut res = CostEstimate { totals: BTreeMap::new(), servings, counted: 0, unpriced: vec![], without_amount: vec![], stale: vec![] };
+    for ingredient in ingredients {
+        let per_gram = ingredient.price.as_ref().and_then(|x| price_per_gram(x).map(|y| (x, y)));
+        match (per_gram, ingredient.amount_grams) {
+            (None, _) => res.unpriced.push(ingredient.name.clone()),
+            (Some(_), None) => res.without_amount.push(ingredient.name.clone()),
+            (Some((price, per_gram)), Some(grams)) => {
+                *res.totals.entry(price.currency.clone()).or_insert(0.0) += per_gram * grams;
+                res.counted += 1;
+                if is_stale(price, today) {
+                    res.stale.push(ingredient.name.clone());
+                }
+            }
+        }
+    }
+    res
+}
+
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::cost::{describe_price, estimate_cost, price_per_gram, CostInput};
+    use crate::models::IngredientPrice;
+
+    fn price(price: f32, quantity: f32, unit: &str, piece_grams: Option<f32>, currency: &str, priced_on: &str) -> IngredientPrice {
+        IngredientPrice { price_id: None, ingredient_id: 1, price, quantity, unit: unit.to_string(), piece_grams, currency: currency.to_string(), priced_on: priced_on.to_string(), created_at: None }
+    }
+
+    #[test]
+    fn converts_units() {
+        assert_eq!(Some(0.0025), price_per_gram(&price(2.5, 1.0, "kg", None, "CHF", "2026-01-01")));
+        assert_eq!(Some(0.005), price_per_gram(&price(3.0, 6.0, "piece", Some(100.0), "CHF", "2026-01-01")));
+        assert_eq!(None, price_per_gram(&price(3.0, 6.0, "piece", None, "CHF", "2026-01-01")));
+        assert_eq!(None, price_per_gram(&price(3.0, 1.0, "bunch", None, "CHF", "2026-01-01")));
+        assert_eq!("3.00 CHF per 6 pieces", describe_price(&price(3.0, 6.0, "piece", Some(100.0), "CHF", "2026-01-01")));
+    }
+
+    #[test]
+    fn cost_per_serving() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let inputs = vec![
+            CostInput { name: "egg".to_string(), amount_grams: Some(240.0), price: Some(price(3.0, 6.0, "piece", Some(60.0), "CHF", "2026-09-01")) },
+            CostInput { name: "tomato".to_string(), amount_grams: Some(400.0), price: Some(price(4.0, 1.0, "kg", None, "CHF", "2024-05-01")) },
+            CostInput { name: "cumin".to_string(), amount_grams: None, price: Some(price(2.0, 50.0, "g", None, "CHF", "2026-09-01")) },
+            CostInput { name: "feta".to_string(), amount_grams: Some(100.0), price: None },
+        ];
+        let res = estimate_cost(&inputs, Some(2), today);
+        assert_eq!(2, res.counted);
+        assert!((res.totals["CHF"] - 3.6).abs() < 0.001);
+        assert!((res.comparable_per_serving().unwrap() - 1.8).abs() < 0.001);
+        assert_eq!((vec!["feta".to_string()], vec!["cumin".to_string()], vec!["tomato".to_string()]), (res.unpriced, res.without_amount, res.stale));
+        let unknown_servings = estimate_cost(&inputs, None, today);
+        assert!((unknown_servings.per_serving()["CHF"] - 3.6).abs() < 0.001);
+        assert_eq!(None, unknown_servings.comparable_per_serving());
+
+        let mixed = vec![
+            CostInput { name: "egg".to_string(), amount_grams: Some(60.0), price: Some(price(0.5, 1.0, "piece", Some(60.0), "CHF", "2026-09-01")) },
+            CostInput { name: "tomato".to_string(), amount_grams: Some(1000.0), price: Some(price(3.0, 1.0, "kg", None, "EUR", "2026-09-01")) },
+        ];
+        let res = estimate_cost(&mixed, Some(2), today);
+        assert_eq!(2, res.per_serving().len());
+        assert_eq!(None, res.comparable_per_serving());
+    }
+}
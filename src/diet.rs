@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EIngredientAttribute {
+    Meat,
+    Fish,
+    Dairy,
+    Egg,
+    Honey,
+    Gluten,
+    Nuts,
+    Alcohol,
+}
+
+impl EIngredientAttribute {
+    pub fn get_attributes() -> Vec<EIngredientAttribute> {
+        vec![EIngredientAttribute::Meat, EIngredientAttribute::Fish, EIngredientAttribute::Dairy, EIngredientAttribute::Egg,
+             EIngredientAttribute::Honey, EIngredientAttribute::Gluten, EIngredientAttribute::Nuts, EIngredientAttribute::Alcohol]
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            EIngredientAttribute::Meat => "meat",
+            EIngredientAttribute::Fish => "fish",
+            EIngredientAttribute::Dairy => "dairy",
+            EIngredientAttribute::Egg => "egg",
+            EIngredientAttribute::Honey => "honey",
+            EIngredientAttribute::Gluten => "gluten",
+            EIngredientAttribute::Nuts => "nuts",
+            EIngredientAttribute::Alcohol => "alcohol",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<EIngredientAttribute> {
+        EIngredientAttribute::get_attributes().into_iter().find(|x| x.code() == code)
+    }
+
+    /// Attributes guests ask about, shown as warnings on the recipe.
+    pub fn is_allergen(&self) -> bool {
+        matches!(self, EIngredientAttribute::Dairy | EIngredientAttribute::Egg | EIngredientAttribute::Fish
+            | EIngredientAttribute::Gluten | EIngredientAttribute::Nuts)
+    }
+}
+
+impl fmt::Display for EIngredientAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EDiet {
+    Vegetarian,
+    Vegan,
+    GlutenFree,
+    DairyFree,
+    NutFree,
+}
+
+impl EDiet {
+    pub fn get_diets() -> Vec<EDiet> {
+        vec![EDiet::Vegetarian, EDiet::Vegan, EDiet::GlutenFree, EDiet::DairyFree, EDiet::NutFree]
+    }
+
+    pub fn value_i32(&self) -> i32 {
+        match self {
+            EDiet::Vegetarian => 1,
+            EDiet::Vegan => 2,
+            EDiet::GlutenFree => 3,
+            EDiet::DairyFree => 4,
+            EDiet::NutFree => 5,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            EDiet::Vegetarian => "vegetarian",
+            EDiet::Vegan => "vegan",
+            EDiet::GlutenFree => "gluten_free",
+            EDiet::DairyFree => "dairy_free",
+            EDiet::NutFree => "nut_free",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<EDiet> {
+        EDiet::get_diets().into_iter().find(|x| x.code() == code)
+    }
+
+    pub fn to_map() -> HashMap<i32, EDiet> {
+        EDiet::get_diets().into_iter().map(|x| (x.value_i32(), x)).collect()
+    }
+
+    pub fn excluded(&self) -> Vec<EIngredientAttribute> {
+        match self {
+            EDiet::Vegetarian => vec![EIngredientAttribute::Meat, EIngredientAttribute::Fish],
+            EDiet::Vegan => vec![EIngredientAttribute::Meat, EIngredientAttribute::Fish, EIngredientAttribute::Dairy,
+                                 EIngredientAttribute::Egg, EIngredientAttribute::Honey],
+            EDiet::GlutenFree => vec![EIngredientAttribute::Gluten],
+            EDiet::DairyFree => vec![EIngredientAttribute::Dairy],
+            EDiet::NutFree => vec![EIngredientAttribute::Nuts],
+        }
+    }
+}
+
+impl fmt::Display for EDiet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EDiet::Vegetarian => write!(f, "vegetarian"),
+            EDiet::Vegan => write!(f, "vegan"),
+            EDiet::GlutenFree => write!(f, "gluten-free"),
+            EDiet::DairyFree => write!(f, "dairy-free"),
+            EDiet::NutFree => write!(f, "nut-free"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RecipeDiet {
+    pub diets: Vec<EDiet>,
+    pub allergens: Vec<EIngredientAttribute>,
+    /// Not every ingredient is classified, so only overrides say anything about diets and allergens may be missing.
+    pub unknown: bool,
+}
+
+/// Attributes of each ingredient of a recipe, `None` for ingredients nobody has classified yet, which could contain anything.
+pub type IngredientAttributes = Option<HashSet<EIngredientAttribute>>;
+
+/// Recipes without ingredients are never classified, an empty list says nothing about the dish.
+pub fn is_classified(ingredient_attributes: &[IngredientAttributes]) -> bool {
+    !ingredient_attributes.is_empty() && ingredient_attributes.iter().all(Option::is_some)
+}
+
+/// A recipe fits a diet when every ingredient is classified and none carries an excluded attribute, overrides win either way.
+pub fn derive_diets(ingredient_attributes: &[IngredientAttributes], overrides: &HashMap<EDiet, bool>) -> Vec<EDiet> {
+    let classified = is_classified(ingredient_attributes);
+    let attributes: HashSet<EIngredientAttribute> = ingredient_attributes.iter().flatten().flatten().copied().collect();
+    EDiet::get_diets().into_iter()
+        .filter(|diet| match overrides.get(diet) {
+            Some(is_diet) => *is_diet,
+            None => classified && diet.excluded().iter().all(|x| !attributes.contains(x)),
+        })
+        .collect()
+}
+
+pub fn collect_allergens(ingredient_attributes: &[IngredientAttributes]) -> Vec<EIngredientAttribute> {
+    let mut allergens: Vec<EIngredientAttribute> = ingredient_attributes.iter().flatten().flatten()
+        .filter(|x| x.is_allergen())
+        .copied()
+        .collect::<HashSet<EIngredientAttribute>>()
+        .into_iter()
+        .collect();
+    allergens.sort();
+    allergens
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::diet::{collect_allergens, derive_diets, EDiet, EIngredientAttribute, IngredientAttributes};
+
+    fn attributes(a: Vec<Vec<EIngredientAttribute>>) -> Vec<IngredientAttributes> {
+        a.into_iter().map(|x| Some(x.into_iter().collect())).collect()
+    }
+
+    #[test]
+    fn eggs_are_vegetarian_not_vegan() {
+        let ingredients = attributes(vec![vec![EIngredientAttribute::Egg], vec![]]);
+        let res = derive_diets(&ingredients, &HashMap::new());
+        assert_eq!(vec![EDiet::Vegetarian, EDiet::GlutenFree, EDiet::DairyFree, EDiet::NutFree], res);
+    }
+
+    #[test]
+    fn overrides_win() {
+        let ingredients = attributes(vec![vec![EIngredientAttribute::Gluten]]);
+        let overrides = HashMap::from([(EDiet::GlutenFree, true), (EDiet::Vegan, false)]);
+        let res = derive_diets(&ingredients, &overrides);
+        assert_eq!(vec![EDiet::Vegetarian, EDiet::GlutenFree, EDiet::DairyFree, EDiet::NutFree], res);
+    }
+
+    #[test]
+    fn unclassified_ingredients_make_diets_unknown() {
+        let mut ingredients = attributes(vec![vec![EIngredientAttribute::Egg]]);
+        ingredients.push(None);
+        assert!(derive_diets(&ingredients, &HashMap::new()).is_empty());
+        assert_eq!(vec![EDiet::Vegetarian], derive_diets(&ingredients, &HashMap::from([(EDiet::Vegetarian, true)])));
+        assert_eq!(vec![EIngredientAttribute::Egg], collect_allergens(&ingredients));
+    }
+
+    #[test]
+    fn no_ingredients_no_diets() {
+        assert!(derive_diets(&[], &HashMap::new()).is_empty());
+        assert_eq!(vec![EDiet::Vegan], derive_diets(&[], &HashMap::from([(EDiet::Vegan, true)])));
+    }
+
+    #[test]
+    fn allergens_sorted_and_unique() {
+        let ingredients = attributes(vec![vec![EIngredientAttribute::Nuts, EIngredientAttribute::Meat], vec![EIngredientAttribute::Dairy, EIngredientAttribute::Nuts]]);
+        assert_eq!(vec![EIngredientAttribute::Dairy, EIngredientAttribute::Nuts], collect_allergens(&ingredients));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod models;
 pub mod parsetypes;
+pub mod print;
+pub mod produce;
 pub mod schema;
 pub mod database;
 pub mod templates;
@@ -7,6 +9,18 @@ pub mod args;
 pub mod queries;
 pub mod strops;
 pub mod secret;
+pub mod comments;
+pub mod cost;
+pub mod cook;
+pub mod diet;
+pub mod markdown;
+pub mod nutrition;
+pub mod rating;
+pub mod recipe_links;
+pub mod recipe_time;
+pub mod revision;
+pub mod seasonality;
+pub mod upload;
 
 pub mod text_search;
 pub mod search;
@@ -0,0 +1,261 @@
+use std::fmt;
+
+use pulldown_cmark::{html, Event, Options, Parser, Tag};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ESection {
+    Ingredients,
+    Method,
+    Notes,
+}
+
+impl ESection {
+    pub fn get_sections() -> Vec<ESection> {
+        vec![ESection::Ingredients, ESection::Method, ESection::Notes]
+    }
+
+    /// Lowercase headings in every recipe language.
+    fn headings(&self) -> &'static [&'static str] {
+        match self {
+            ESection::Ingredients => &["ingredients", "zutaten", "ingrédients", "ingredienti"],
+            ESection::Method => &["method", "preparation", "directions", "instructions", "zubereitung", "préparation", "preparazione", "procedimento"],
+            ESection::Notes => &["notes", "tips", "notizen", "tipps", "remarques", "note", "consigli"],
+        }
+    }
+
+    /// A line on its own like `Method`, `## Zutaten` or `**Notes:**`.
+    pub fn from_heading(line: &str) -> Option<ESection> {
+        let name = line.trim()
+            .trim_start_matches('#')
+            .trim()
+            .trim_matches('*')
+            .trim_end_matches(':')
+            .trim()
+            .to_lowercase();
+        ESection::get_sections().into_iter().find(|x| x.headings().contains(&name.as_str()))
+    }
+}
+
+impl fmt::Display for ESection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ESection::Ingredients => write!(f, "Ingredients"),
+            ESection::Method => write!(f, "Method"),
+            ESection::Notes => write!(f, "Notes"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecipeSection {
+    pub section: Option<ESection>,
+    pub content: String,
+}
+
+/// Text before the first recognised heading is kept as a section without a name.
+pub fn split_sections(text: &str) -> Vec<RecipeSection> {
+    let mut res = vec![];
+    let mut cur = RecipeSection { section: None, content: String::new() };
+    for line in text.lines() {
+        match ESection::from_heading(line) {
+            Some(section) => {
+                if cur.section.is_some() || !cur.content.trim().is_empty() {
+                    res.push(cur);
+                }
+                cur = RecipeSection { section: Some(section), content: String::new() };
+            }
+            None => {
+                cur.content.push_str(line);
+                cur.content.push('\n');
+            }
+        }
+    }
+    if cur.section.is_some() || !cur.content.trim().is_empty() {
+        res.push(cur);
+    }
+    res
+}
+
+/// The text after a list marker, `None` for lines that are not list items.
+fn list_item_text(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    let digits = line.chars().take_while(|x| x.is_ascii_digit()).count();
+    if ["- ", "* ", "+ "].iter().any(|x| line.starts_with(x)) {
+        return Some(line[2..].trim());
+    }
+    if digits > 0 && (line[digits..].starts_with(". ") || line[digits..].starts_with(") ")) {
+        return Some(line[digits + 2..].trim());
+    }
+    None
+}
+
+fn is_list_item(line: &str) -> bool {
+    list_item_text(line).is_some()
+}
+
+/// Plain lines become one list item each, text that already has a list is left alone.
+fn as_list(content: &str, numbered: bool) -> String {
+    if content.lines().any(is_list_item) {
+        return content.to_string();
+    }
+    content.lines()
+        .filter(|x| !x.trim().is_empty())
+        .enumerate()
+        .map(|(i, x)| if numbered { format!("{}. {}\n", i + 1, x.trim()) } else { format!("- {}\n", x.trim()) })
+        .collect()
+}
+
+/// Sections with ingredients as a list and method lines as numbered steps, still markdown.
+fn listed_sections(text: &str) -> Vec<(Option<ESection>, String)> {
+    split_sections(text).into_iter()
+        .map(|x| {
+            let content = match x.section {
+                Some(ESection::Ingredients) => as_list(x.content.as_str(), false),
+                Some(ESection::Method) => as_list(x.content.as_str(), true),
+                _ => x.content,
+            };
+            (x.section, content)
+        })
+        .collect()
+}
+
+/// Ingredients are shown as a list and method lines as numbered steps.
+pub fn render_recipe_text(text: &str) -> Vec<(Option<ESection>, String)> {
+    listed_sections(text).into_iter()
+        .map(|(section, content)| (section, render_markdown(content.as_str())))
+        .collect()
+}
+
+/// The same structure as `render_recipe_text` as plain lines, for output that is not html.
+pub fn plain_recipe_text(text: &str) -> Vec<(Option<ESection>, Vec<String>)> {
+    listed_sections(text).into_iter()
+        .map(|(section, content)| (section, plain_lines(content.as_str())))
+        .collect()
+}
+
+/// One line per paragraph, heading, list item or table row, list items keep a marker.
+fn plain_lines(a: &str) -> Vec<String> {
+    let mut res = vec![];
+    let mut cur = String::new();
+    let mut lists: Vec<Option<u64>> = vec![];
+    let finish = |cur: &mut String, res: &mut Vec<String>| {
+        if !cur.trim().is_empty() {
+            res.push(cur.trim_end().to_string());
+        }
+        cur.clear();
+    };
+    for event in Parser::new_ext(a, Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES) {
+        match event {
+            Event::Start(Tag::List(start)) => lists.push(start),
+            Event::End(Tag::List(_)) => {
+                lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                finish(&mut cur, &mut res);
+                cur.push_str("  ".repeat(lists.len().saturating_sub(1)).as_str());
+                match lists.last_mut() {
+                    Some(Some(number)) => {
+                        cur.push_str(format!("{}. ", number).as_str());
+                        *number += 1;
+                    }
+                    _ => cur.push_str("- "),
+                }
+            }
+            Event::Text(x) | Event::Code(x) => cur.push_str(x.as_ref()),
+            Event::SoftBreak | Event::HardBreak => cur.push(' '),
+            Event::End(Tag::TableCell) => cur.push_str("  "),
+            Event::End(Tag::Paragraph | Tag::Item | Tag::Heading(..) | Tag::TableHead | Tag::TableRow | Tag::CodeBlock(_)) => finish(&mut cur, &mut res),
+            _ => {}
+        }
+    }
+    finish(&mut cur, &mut res);
+    res
+}
+
+/// Method steps for cook mode, text without any heading counts as method.
+/// Lines following a list item belong to that step.
+pub fn cook_steps(text: &str) -> Vec<String> {
+    let sections = split_sections(text);
+    let has_method = sections.iter().any(|x| x.section == Some(ESection::Method));
+    let mut steps: Vec<String> = vec![];
+    for section in sections.iter().filter(|x| x.section == if has_method { Some(ESection::Method) } else { None }) {
+        let has_list = section.content.lines().any(is_list_item);
+        for line in section.content.lines().filter(|x| !x.trim().is_empty()) {
+            match (list_item_text(line), steps.last_mut()) {
+                (Some(item), _) => steps.push(item.to_string()),
+                (None, Some(last)) if has_list => {
+                    last.push(' ');
+                    last.push_str(line.trim());
+                }
+                (None, _) => steps.push(line.trim().to_string()),
+            }
+        }
+    }
+    steps
+}
+
+/// User input, raw html in the markdown is stripped.
+pub fn render_markdown(a: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, Parser::new_ext(a, options));
+    ammonia::clean(unsafe_html.as_str())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::markdown::{cook_steps, plain_recipe_text, render_markdown, render_recipe_text, split_sections, ESection};
+
+    #[test]
+    fn markdown_is_sanitized() {
+        assert_eq!("<p><strong>less</strong> salt</p>\n", render_markdown("**less** salt"));
+        assert!(!render_markdown("<script>alert(1)</script>").contains("<script"));
+        assert!(!render_markdown("[x](javascript:alert(1))").contains("href"));
+    }
+
+    #[test]
+    fn recognises_headings() {
+        assert_eq!(Some(ESection::Method), ESection::from_heading("## Method"));
+        assert_eq!(Some(ESection::Ingredients), ESection::from_heading("**Zutaten:**"));
+        assert_eq!(Some(ESection::Notes), ESection::from_heading(" notes "));
+        assert_eq!(None, ESection::from_heading("Method: fry the onions"));
+    }
+
+    #[test]
+    fn splits_sections() {
+        let res = split_sections("From grandma\n\nIngredients\n4 eggs\nMethod\nFry");
+        let sections: Vec<Option<ESection>> = res.iter().map(|x| x.section).collect();
+        assert_eq!(vec![None, Some(ESection::Ingredients), Some(ESection::Method)], sections);
+        assert_eq!("4 eggs\n", res[1].content);
+    }
+
+    #[test]
+    fn method_lines_become_steps() {
+        let res = render_recipe_text("Ingredients\n4 eggs\n1 onion\n\nMethod\nFry the onion.\n\nAdd the eggs.");
+        assert_eq!("<ul>\n<li>4 eggs</li>\n<li>1 onion</li>\n</ul>\n", res[0].1);
+        assert_eq!("<ol>\n<li>Fry the onion.</li>\n<li>Add the eggs.</li>\n</ol>\n", res[1].1);
+        let numbered = render_recipe_text("Method\n1. Fry\n2. Serve");
+        assert_eq!("<ol>\n<li>Fry</li>\n<li>Serve</li>\n</ol>\n", numbered[0].1);
+    }
+
+    #[test]
+    fn steps_for_cook_mode() {
+        let text = "Ingredients\n- 4 eggs\n\nMethod\n1. Fry onions\n   until soft.\n2. Add eggs.\n\nNotes\nServe hot";
+        assert_eq!(vec!["Fry onions until soft.", "Add eggs."], cook_steps(text));
+        assert_eq!(vec!["Fry onions", "Add eggs"], cook_steps("Fry onions\n\nAdd eggs\n\nNotes\nServe hot"));
+        assert!(cook_steps("Ingredients\n- 4 eggs").is_empty());
+    }
+
+    #[test]
+    fn plain_text_keeps_structure() {
+        let res = plain_recipe_text("Ingredients\n4 *eggs*\n\nMethod\nFry the `onion`.\nAdd\n\nNotes\nGood with **bread**\nand butter");
+        assert_eq!(vec![
+            (Some(ESection::Ingredients), vec!["- 4 eggs".to_string()]),
+            (Some(ESection::Method), vec!["1. Fry the onion.".to_string(), "2. Add".to_string()]),
+            (Some(ESection::Notes), vec!["Good with bread and butter".to_string()]),
+        ], res);
+    }
+}
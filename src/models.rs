@@ -3,13 +3,18 @@ use diesel::prelude::*;
 
 
 use super::schema::book;
+use super::schema::collection;
+use super::schema::collection_recipe;
+use super::schema::collection_share;
 use super::schema::course;
 use super::schema::ingredient;
 use super::schema::recipe;
 use super::schema::recipe_comment;
 use super::schema::recipe_ingredient;
 use super::schema::recipe_text;
+use super::schema::saved_search;
 use super::schema::season;
+use super::schema::synonym;
 use super::schema::tried;
 use super::schema::user;
 
@@ -25,7 +30,13 @@ pub struct FullRecipe {
     pub recipe_url: Option<String>,
     pub created_at: Option<f32>,
     pub page: Option<i32>,
-
+    pub language: Option<String>,
+    pub servings: Option<i32>,
+    pub prep_minutes: Option<i32>,
+    pub cook_minutes: Option<i32>,
+    pub total_minutes: Option<i32>,
+    pub difficulty: Option<String>,
+    pub modified_at: Option<String>,
 }
 
 
@@ -50,6 +61,12 @@ pub struct InsertRecipeWithUrl {
     pub page: Option<i32>,
     pub recipe_id: Option<i32>,
     pub recipe_url: Option<String>,
+    pub language: Option<String>,
+    pub servings: Option<i32>,
+    pub prep_minutes: Option<i32>,
+    pub cook_minutes: Option<i32>,
+    pub total_minutes: Option<i32>,
+    pub difficulty: Option<String>,
 }
 
 
@@ -64,6 +81,12 @@ pub struct FullInsertRecipe {
     pub recipe_url: Option<String>,
     pub created_at: Option<f32>,
     pub page: Option<i32>,
+    pub language: Option<String>,
+    pub servings: Option<i32>,
+    pub prep_minutes: Option<i32>,
+    pub cook_minutes: Option<i32>,
+    pub total_minutes: Option<i32>,
+    pub difficulty: Option<String>,
 }
 
 #[derive(Queryable)]
@@ -96,6 +119,7 @@ pub struct QBook {
     pub book_id: Option<i32>,
     pub book_name: Option<String>,
     pub created_at: Option<f32>,
+    pub language: Option<String>,
 
 }
 
@@ -114,18 +138,16 @@ impl InsertBook {
     }
 }
 
-#[derive(Queryable)]
+#[derive(Queryable, Clone)]
 #[diesel(table_name = season)]
 pub struct QSeason {
     pub season_id: Option<i32>,
-    pub name: String,
-}
-
-
-impl QSeason {
-    pub fn new(season_id: Option<i32>, name: String) -> QSeason {
-        QSeason { season_id: season_id, name: name }
-    }
+    pub tag_name: Option<String>,
+    pub created_at: Option<f32>,
+    pub start_month: Option<i32>,
+    pub end_month: Option<i32>,
+    pub position: i32,
+    pub all_year: bool,
 }
 
 #[derive(Insertable)]
@@ -147,6 +169,7 @@ pub struct Ingredient {
     pub id: Option<i32>,
     pub name: Option<String>,
     pub created_at: Option<f32>,
+    pub classified: bool,
 
 }
 
@@ -163,6 +186,7 @@ pub struct RecipeIngredient {
     pub recipe_id: i32,
     pub ingredient_id: i32,
     pub created_at: Option<f32>,
+    pub amount_grams: Option<f32>,
 
 }
 
@@ -187,6 +211,8 @@ pub struct User {
     pub email: String,
     pub pw_hash: String,
     pub created_at: Option<f32>,
+    pub is_admin: bool,
+    pub display_name: Option<String>,
 }
 
 
@@ -198,7 +224,7 @@ pub struct Tried {
     pub created_at: Option<f32>,
 }
 
-#[derive(Queryable, QueryableByName)]
+#[derive(Queryable, QueryableByName, Clone)]
 #[diesel(table_name = recipe_comment)]
 pub struct Comment {
     pub comment_id: i32,
@@ -206,6 +232,8 @@ pub struct Comment {
     pub recipe_id: i32,
     pub content: String,
     pub created_at: f32,
+    pub parent_id: Option<i32>,
+    pub modified_at: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -214,6 +242,7 @@ pub struct InsertComment {
     pub user_id: i32,
     pub recipe_id: i32,
     pub content: String,
+    pub parent_id: Option<i32>,
 }
 
 
@@ -234,3 +263,222 @@ pub struct InsertRecipeText {
     pub content: String,
 }
 
+
+#[derive(Queryable, Clone)]
+#[diesel(table_name = synonym)]
+pub struct Synonym {
+    pub synonym_id: Option<i32>,
+    pub group_id: i32,
+    pub term: String,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = synonym)]
+pub struct InsertSynonym {
+    pub group_id: i32,
+    pub term: String,
+}
+
+#[derive(Queryable, Clone)]
+#[diesel(table_name = saved_search)]
+pub struct SavedSearch {
+    pub saved_search_id: Option<i32>,
+    pub user_id: i32,
+    pub search_name: String,
+    pub query: String,
+    pub smart_collection: bool,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = saved_search)]
+pub struct InsertSavedSearch {
+    pub user_id: i32,
+    pub search_name: String,
+    pub query: String,
+    pub smart_collection: bool,
+}
+
+#[derive(Queryable, Clone)]
+#[diesel(table_name = collection)]
+pub struct QCollection {
+    pub collection_id: Option<i32>,
+    pub user_id: i32,
+    pub collection_name: String,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = collection)]
+pub struct InsertCollection {
+    pub user_id: i32,
+    pub collection_name: String,
+}
+
+#[derive(Queryable, Clone)]
+#[diesel(table_name = collection_recipe)]
+pub struct CollectionRecipe {
+    pub collection_id: i32,
+    pub recipe_id: i32,
+    pub position: i32,
+    pub note: Option<String>,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = collection_recipe)]
+pub struct InsertCollectionRecipe {
+    pub collection_id: i32,
+    pub recipe_id: i32,
+    pub position: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = collection_share)]
+pub struct InsertCollectionShare {
+    pub collection_id: i32,
+    pub user_id: i32,
+}
+
+#[derive(Queryable, Clone)]
+pub struct Tag {
+    pub tag_id: Option<i32>,
+    pub tag_name: String,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Queryable, Clone)]
+pub struct RecipeTag {
+    pub recipe_id: i32,
+    pub tag_id: i32,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Queryable, Clone)]
+pub struct IngredientAttribute {
+    pub ingredient_id: i32,
+    pub attribute: String,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Queryable, Clone)]
+pub struct ProduceMonth {
+    pub ingredient_id: i32,
+    pub region: String,
+    pub month: i32,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Queryable, Clone)]
+pub struct RecipeDietOverride {
+    pub recipe_id: i32,
+    pub diet: String,
+    pub is_diet: bool,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Queryable, Clone, Debug, PartialEq)]
+pub struct RecipeSeason {
+    pub recipe_id: i32,
+    pub season_id: i32,
+    pub start_month: Option<i32>,
+    pub end_month: Option<i32>,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Queryable, Clone, Debug, PartialEq)]
+pub struct IngredientPrice {
+    pub price_id: Option<i32>,
+    pub ingredient_id: i32,
+    pub price: f32,
+    pub quantity: f32,
+    pub unit: String,
+    pub piece_grams: Option<f32>,
+    pub currency: String,
+    pub priced_on: String,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Queryable, Clone, Debug, PartialEq)]
+pub struct Equipment {
+    pub equipment_id: Option<i32>,
+    pub name: String,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Queryable, Clone, Debug, PartialEq)]
+pub struct RecipeLink {
+    pub recipe_id: i32,
+    pub linked_recipe_id: i32,
+    pub link_type: String,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Queryable, Clone, Debug, PartialEq)]
+pub struct Nutrient {
+    pub nutrient_id: Option<i32>,
+    pub source: String,
+    pub source_code: String,
+    pub food_name: String,
+    pub energy_kcal: Option<f32>,
+    pub protein_g: Option<f32>,
+    pub fat_g: Option<f32>,
+    pub carbs_g: Option<f32>,
+    pub fibre_g: Option<f32>,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Queryable, Clone)]
+pub struct RecipeRating {
+    pub user_id: i32,
+    pub recipe_id: i32,
+    pub rating: Option<i32>,
+    pub cook_again: bool,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Queryable, Clone)]
+pub struct Cooked {
+    pub cooked_id: Option<i32>,
+    pub user_id: i32,
+    pub recipe_id: i32,
+    pub cooked_on: String,
+    pub created_at: Option<f32>,
+    pub notes: Option<String>,
+    pub servings: Option<i32>,
+    pub photo: Option<String>,
+}
+
+#[derive(Queryable, Clone)]
+pub struct RecipeImage {
+    pub image_id: Option<i32>,
+    pub recipe_id: i32,
+    pub file_name: String,
+    pub is_cover: bool,
+    pub position: i32,
+    pub created_at: Option<f32>,
+}
+
+#[derive(Queryable, Clone)]
+pub struct RecipeRevision {
+    pub revision_id: Option<i32>,
+    pub recipe_id: i32,
+    pub user_id: Option<i32>,
+    pub recipe_name: Option<String>,
+    pub primary_season: i32,
+    pub course_id: i32,
+    pub book_id: Option<i32>,
+    pub page: Option<i32>,
+    pub recipe_url: Option<String>,
+    pub language: Option<String>,
+    pub ingredients: String,
+    pub tags: String,
+    pub content: String,
+    pub created_at: String,
+    pub servings: Option<i32>,
+    pub prep_minutes: Option<i32>,
+    pub cook_minutes: Option<i32>,
+    pub total_minutes: Option<i32>,
+    pub difficulty: Option<String>,
+}
@@ -0,0 +1,305 @@
+use std::fmt;
+use std::ops::Add;
+
+use crate::models::Nutrient;
+
+/// Energy and macronutrients, per 100 g in the nutrient table and absolute once amounts are applied.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Nutrients {
+    pub energy_kcal: f32,
+    pub protein_g: f32,
+    pub fat_g: f32,
+    pub carbs_g: f32,
+    pub fibre_g: f32,
+}
+
+impl Nutrients {
+    pub fn scaled(&self, factor: f32) -> Nutrients {
+        Nutrients {
+            energy_kcal: self.energy_kcal * factor,
+            protein_g: self.protein_g * factor,
+            fat_g: self.fat_g * factor,
+            carbs_g: self.carbs_g * factor,
+            fibre_g: self.fibre_g * factor,
+        }
+    }
+}
+
+impl Add for Nutrients {
+    type Output = Nutrients;
+
+    fn add(self, other: Nutrients) -> Nutrients {
+        Nutrients {
+            energy_kcal: self.energy_kcal + other.energy_kcal,
+            protein_g: self.protein_g + other.protein_g,
+            fat_g: self.fat_g + other.fat_g,
+            carbs_g: self.carbs_g + other.carbs_g,
+            fibre_g: self.fibre_g + other.fibre_g,
+        }
+    }
+}
+
+/// Values the source does not list count as zero.
+impl From<&Nutrient> for Nutrients {
+    fn from(a: &Nutrient) -> Self {
+        Nutrients {
+            energy_kcal: a.energy_kcal.unwrap_or(0.0),
+            protein_g: a.protein_g.unwrap_or(0.0),
+            fat_g: a.fat_g.unwrap_or(0.0),
+            carbs_g: a.carbs_g.unwrap_or(0.0),
+            fibre_g: a.fibre_g.unwrap_or(0.0),
+        }
+    }
+}
+
+impl fmt::Display for Nutrients {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.0} kcal, {:.1} g protein, {:.1} g fat, {:.1} g carbs, {:.1} g fibre",
+               self.energy_kcal, self.protein_g, self.fat_g, self.carbs_g, self.fibre_g)
+    }
+}
+
+/// An ingredient of a recipe as far as nutrition is concerned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NutritionInput {
+    pub name: String,
+    pub amount_grams: Option<f32>,
+    pub per_100g: Option<Nutrients>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NutritionEstimate {
+    pub total: Nutrients,
+    pub servings: Option<i32>,
+    pub counted: usize,
+    pub unmapped: Vec<String>,
+    pub without_amount: Vec<String>,
+}
+
+impl NutritionEstimate {
+    /// The whole recipe when the number of servings is unknown.
+    pub fn per_serving(&self) -> Nutrients {
+        self.total.scaled(1.0 / self.servings.unwrap_or(1).max(1) as f32)
+    }
+
+    /// Nothing could be counted, so there is no estimate to show.
+    pub fn is_empty(&self) -> bool {
+        self.counted == 0
+    }
+}
+
+/// Ingredients without a nutrient mapping or without an amount are left out and listed instead.
+pub fn estimate_nutrition(ingredients: &[NutritionInput], servings: Option<i32>) -> NutritionEstimate {
+    let mut res = NutritionEstimate { total: Nutrients::default(), servings, counted: 0, unmapped: vec![], without_amount: vec![] };
+    for ingredient in ingredients {
+        match (ingredient.per_100g, ingredient.amount_grams) {
+            (None, _) => res.unmapped.push(ingredient.name.clone()),
+            (Some(_), None) => res.without_amount.push(ingredient.name.clone()),
+            (Some(per_100g), Some(grams)) => {
+                res.total = res.total + per_100g.scaled(grams / 100.0);
+                res.counted += 1;
+            }
+        }
+    }
+    res
+}
+
+/// A row of an imported nutrient file before it is stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NutrientRow {
+    pub source_code: String,
+    pub food_name: String,
+    pub energy_kcal: Option<f32>,
+    pub protein_g: Option<f32>,
+    pub fat_g: Option<f32>,
+    pub carbs_g: Option<f32>,
+    pub fibre_g: Option<f32>,
+}
+
+/// Splits a line of a csv file, quoted fields may contain the separator and doubled quotes.
+pub fn split_csv_line(line: &str, separator: char) -> Vec<String> {
+    let mut res = vec![];
+    let mut cur = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches(['\r', '\n']).chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cur.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            x if x == separator && !quoted => res.push(std::mem::take(&mut cur)),
+            x => cur.push(x),
+        }
+    }
+    res.push(cur);
+    res
+}
+
+/// Numbers like "3,5", "< 0,5" or "traces" as found in CIQUAL, "-" and empty cells are missing values.
+fn parse_amount(a: &str) -> Option<f32> {
+    let a = a.trim().trim_start_matches('<').trim();
+    if a.eq_ignore_ascii_case("traces") {
+        return Some(0.0);
+    }
+    a.replace(',', ".").parse::<f32>().ok()
+}
+
+/// Column positions found by their headings, which differ between USDA, CIQUAL and the bundled file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct NutrientColumns {
+    code: usize,
+    name: usize,
+    energy_kcal: Option<usize>,
+    protein_g: Option<usize>,
+    fat_g: Option<usize>,
+    carbs_g: Option<usize>,
+    fibre_g: Option<usize>,
+}
+
+impl NutrientColumns {
+    fn from_headings(headings: &[String]) -> Option<NutrientColumns> {
+        let headings: Vec<String> = headings.iter().map(|x| x.trim().to_lowercase()).collect();
+        let exact = |names: &[&str]| headings.iter().position(|x| names.contains(&x.as_str()));
+        let containing = |parts: &[&str]| headings.iter().position(|x| parts.iter().any(|y| x.contains(y)));
+        Some(NutrientColumns {
+            code: exact(&["code", "fdc_id", "ndb_no", "alim_code"])?,
+            name: exact(&["name", "description", "alim_nom_eng", "alim_nom_fr"])?,
+            energy_kcal: containing(&["kcal"]),
+            protein_g: containing(&["protein", "protéines"]),
+            fat_g: containing(&["fat", "lipides"]),
+            carbs_g: containing(&["carbohydrate", "carbs", "glucides"]),
+            fibre_g: containing(&["fiber", "fibre"]),
+        })
+    }
+}
+
+/// Rows of a nutrient csv with values per 100 g, `;` separated files like CIQUAL are recognised by their first line.
+/// `None` when the code or name column is missing.
+pub fn parse_nutrient_csv(content: &str) -> Option<Vec<NutrientRow>> {
+    let mut lines = content.lines().filter(|x| !x.trim().is_empty());
+    let first = lines.next()?;
+    let separator = if first.matches(';').count() > first.matches(',').count() { ';' } else { ',' };
+    let columns = NutrientColumns::from_headings(&split_csv_line(first, separator))?;
+    let res = lines
+        .map(|x| split_csv_line(x, separator))
+        .filter_map(|cells| {
+            let cell = |i: Option<usize>| i.and_then(|y| cells.get(y)).and_then(|y| parse_amount(y));
+            let source_code = cells.get(columns.code)?.trim().to_string();
+            let food_name = cells.get(columns.name)?.trim().to_string();
+            if source_code.is_empty() || food_name.is_empty() {
+                return None;
+            }
+            Some(NutrientRow {
+                source_code,
+                food_name,
+                energy_kcal: cell(columns.energy_kcal),
+                protein_g: cell(columns.protein_g),
+                fat_g: cell(columns.fat_g),
+                carbs_g: cell(columns.carbs_g),
+                fibre_g: cell(columns.fibre_g),
+            })
+        })
+        .collect();
+    Some(res)
+}
+
+/// Lowercase and without a plural s, "Tomatoes" and "tomato" are the same.
+fn normalize_food(a: &str) -> String {
+    let a = a.trim().to_lowercase();
+    a.strip_suffix("es").filter(|x| x.ends_with('o'))
+        .or(a.strip_suffix('s'))
+        .unwrap_or(a.as_str())
+        .to_string()
+}
+
+/// Names a food is known by, "Eggplant (aubergine), raw" is both an eggplant and an aubergine.
+fn food_aliases(food_name: &str) -> Vec<String> {
+    let first = food_name.split(',').next().unwrap_or("");
+    let (main, alias) = match first.split_once('(') {
+        Some((main, rest)) => (main, rest.split(')').next()),
+        None => (first, None),
+    };
+    std::iter::once(main).chain(alias).map(normalize_food).filter(|x| !x.is_empty()).collect()
+}
+
+/// The first food whose name before the first comma is the ingredient, for mapping ingredients after an import.
+pub fn match_food<'a>(ingredient_name: &str, foods: &'a [Nutrient]) -> Option<&'a Nutrient> {
+    let wanted = normalize_food(ingredient_name);
+    foods.iter().find(|x| food_aliases(x.food_name.as_str()).contains(&wanted))
+}
+
+/// How a food is offered in the ingredient admin, the source and code make it unique.
+pub fn food_label(a: &Nutrient) -> String {
+    format!("{}:{} {}", a.source, a.source_code, a.food_name)
+}
+
+/// The source and code at the start of a `food_label`.
+pub fn parse_food_label(a: &str) -> Option<(&str, &str)> {
+    a.trim().split(' ').next()?.split_once(':').filter(|x| !x.0.is_empty() && !x.1.is_empty())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::models::Nutrient;
+    use crate::nutrition::{estimate_nutrition, food_label, match_food, parse_food_label, parse_nutrient_csv, split_csv_line, NutritionInput, Nutrients};
+
+    fn food(nutrient_id: i32, food_name: &str) -> Nutrient {
+        Nutrient {
+            nutrient_id: Some(nutrient_id), source: "basic".to_string(), source_code: nutrient_id.to_string(), food_name: food_name.to_string(),
+            energy_kcal: None, protein_g: None, fat_g: None, carbs_g: None, fibre_g: None, created_at: None,
+        }
+    }
+
+    #[test]
+    fn splits_quoted_fields() {
+        assert_eq!(vec!["1", "Egg, whole", "143"], split_csv_line("1,\"Egg, whole\",143\r", ','));
+        assert_eq!(vec!["a \"b\"", ""], split_csv_line("\"a \"\"b\"\"\";", ';'));
+    }
+
+    #[test]
+    fn reads_usda_and_ciqual_headings() {
+        let usda = "fdc_id,description,Energy (kcal),Protein (g),Total lipid (fat) (g),Carbohydrate (g),Fiber (g)\n\
+                    171287,\"Egg, whole, raw\",143,12.6,9.5,0.7,0\n,missing code,1,1,1,1,1\n";
+        let res = parse_nutrient_csv(usda).unwrap();
+        assert_eq!(1, res.len());
+        assert_eq!(("Egg, whole, raw", Some(143.0), Some(9.5)), (res[0].food_name.as_str(), res[0].energy_kcal, res[0].fat_g));
+        let ciqual = "alim_code;alim_nom_eng;Energie (kcal/100 g);Protéines, N x 6.25 (g/100 g);Lipides (g/100 g);Glucides (g/100 g);Fibres alimentaires (g/100 g)\n\
+                      20047;Tomato, raw;18,1;0,86;< 0,5;traces;-\n";
+        let res = parse_nutrient_csv(ciqual).unwrap();
+        assert_eq!((Some(18.1), Some(0.5), Some(0.0), None), (res[0].energy_kcal, res[0].fat_g, res[0].carbs_g, res[0].fibre_g));
+        assert_eq!(None, parse_nutrient_csv("id,kcal\n1,2"));
+    }
+
+    #[test]
+    fn matches_ingredients_to_foods() {
+        let foods = vec![food(1, "Eggplant (aubergine), raw"), food(2, "Tomatoes, red, raw"), food(3, "Egg, whole, raw")];
+        let found = |x: &str| match_food(x, &foods).and_then(|y| y.nutrient_id);
+        assert_eq!(Some(1), found("Aubergine"));
+        assert_eq!(Some(2), found("tomato"));
+        assert_eq!(Some(3), found("eggs"));
+        assert_eq!(None, found("whole"));
+        assert_eq!("basic:2 Tomatoes, red, raw", food_label(&foods[1]));
+        assert_eq!(Some(("basic", "2")), parse_food_label(food_label(&foods[1]).as_str()));
+        assert_eq!(None, parse_food_label("Tomatoes"));
+    }
+
+    #[test]
+    fn estimates_per_serving() {
+        let egg = Nutrients { energy_kcal: 143.0, protein_g: 12.6, fat_g: 9.5, carbs_g: 0.7, fibre_g: 0.0 };
+        let ingredients = vec![
+            NutritionInput { name: "egg".to_string(), amount_grams: Some(200.0), per_100g: Some(egg) },
+            NutritionInput { name: "tomato".to_string(), amount_grams: None, per_100g: Some(Nutrients::default()) },
+            NutritionInput { name: "za'atar".to_string(), amount_grams: Some(5.0), per_100g: None },
+        ];
+        let res = estimate_nutrition(&ingredients, Some(2));
+        assert_eq!(1, res.counted);
+        assert_eq!(vec!["za'atar"], res.unmapped);
+        assert_eq!(vec!["tomato"], res.without_amount);
+        assert_eq!(143.0, res.per_serving().energy_kcal);
+        assert_eq!(286.0, estimate_nutrition(&ingredients, None).per_serving().energy_kcal);
+        assert!(estimate_nutrition(&ingredients[1..], None).is_empty());
+    }
+}
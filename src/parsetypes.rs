@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::fmt;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -18,6 +17,7 @@ impl fmt::Display for ParseRecipe{
 }
 
 
+/// Season tags of the imported markdown files, the seasons themselves live in the `season` table.
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
 pub enum ESeason {
     Summer,
@@ -38,18 +38,6 @@ impl ESeason {
         }
     }
 
-    pub fn value_rofl(&self) -> usize {
-        return ESeason::value(*self);
-    }
-
-    pub fn value_i32(&self) -> i32 {
-        ESeason::value(*self) as i32
-    }
-
-    pub fn get_seasons() -> Vec<ESeason> {
-        return vec![ESeason::Summer, ESeason::Autumn, ESeason::Winter, ESeason::Spring, ESeason::Independent];
-    }
-
     pub fn to_string<'a>(seas: &'a ESeason) -> &'a str {
         match seas {
             ESeason::Summer => "Summer",
@@ -73,13 +61,6 @@ impl ESeason {
         }
     }
 
-    pub fn to_map() -> HashMap<usize, ESeason> {
-        return HashMap::from([(1, ESeason::Summer), (2, ESeason::Autumn), (3, ESeason::Winter), (4, ESeason::Spring), (5, ESeason::Independent)]);
-    }
-
-    pub fn get_by_db_id(id: i32) -> ESeason {
-        return *(ESeason::to_map().get(&(id as usize)).unwrap());
-    }
 }
 
 
@@ -0,0 +1,225 @@
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+
+use crate::markdown::{plain_recipe_text, ESection};
+
+const MARGIN_MM: f32 = 12.0;
+const PT_TO_MM: f32 = 0.3528;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EPaperFormat {
+    A4,
+    A5,
+    IndexCard,
+}
+
+impl EPaperFormat {
+    pub fn get_formats() -> Vec<EPaperFormat> {
+        vec![EPaperFormat::A5, EPaperFormat::IndexCard, EPaperFormat::A4]
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            EPaperFormat::A4 => "a4",
+            EPaperFormat::A5 => "a5",
+            EPaperFormat::IndexCard => "card",
+        }
+    }
+
+    /// Unknown codes fall back to A5.
+    pub fn from_code(code: Option<&str>) -> EPaperFormat {
+        EPaperFormat::get_formats().into_iter()
+            .find(|x| Some(x.code()) == code)
+            .unwrap_or(EPaperFormat::A5)
+    }
+
+    /// Width and height, index cards are 8 x 5 inch in landscape.
+    pub fn size_mm(&self) -> (f32, f32) {
+        match self {
+            EPaperFormat::A4 => (210.0, 297.0),
+            EPaperFormat::A5 => (148.0, 210.0),
+            EPaperFormat::IndexCard => (203.2, 127.0),
+        }
+    }
+
+    /// Value for the css `@page` size.
+    pub fn css_size(&self) -> &'static str {
+        match self {
+            EPaperFormat::A4 => "A4",
+            EPaperFormat::A5 => "A5",
+            EPaperFormat::IndexCard => "8in 5in",
+        }
+    }
+}
+
+impl std::fmt::Display for EPaperFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EPaperFormat::A4 => write!(f, "A4"),
+            EPaperFormat::A5 => write!(f, "A5"),
+            EPaperFormat::IndexCard => write!(f, "Index card"),
+        }
+    }
+}
+
+/// What goes on a card, ingredients from the database are used when the text has no ingredients section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintRecipe {
+    pub name: String,
+    pub course: String,
+    pub season: String,
+    pub reference: Option<String>,
+    pub ingredients: Vec<String>,
+    pub text: String,
+}
+
+/// "Jerusalem, p. 40" for recipes in a physical book.
+pub fn book_reference(book_name: Option<&str>, page: Option<i32>) -> Option<String> {
+    match (book_name, page) {
+        (Some(name), Some(page)) => Some(format!("{}, p. {}", name, page)),
+        (Some(name), None) => Some(name.to_string()),
+        (None, _) => None,
+    }
+}
+
+/// Greedy word wrap, words longer than a line are split.
+pub fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut res = vec![];
+    let mut cur = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > max_chars {
+            if !cur.is_empty() {
+                res.push(std::mem::take(&mut cur));
+            }
+            res.push(word.drain(..max_chars).collect());
+        }
+        let word: String = word.into_iter().collect();
+        if !cur.is_empty() && cur.chars().count() + 1 + word.chars().count() > max_chars {
+            res.push(std::mem::take(&mut cur));
+        }
+        if !cur.is_empty() {
+            cur.push(' ');
+        }
+        cur.push_str(word.as_str());
+    }
+    if !cur.is_empty() {
+        res.push(cur);
+    }
+    res
+}
+
+struct PdfWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    format: EPaperFormat,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    y: f32,
+    pages: usize,
+}
+
+impl PdfWriter {
+    fn new_page(&mut self) {
+        let (width, height) = self.format.size_mm();
+        self.pages += 1;
+        let (page, layer) = self.doc.add_page(Mm(width), Mm(height), format!("Page {}", self.pages));
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = height - MARGIN_MM;
+    }
+
+    /// Helvetica averages about half an em per character, which is close enough for wrapping.
+    fn write(&mut self, text: &str, size: f32, bold: bool) {
+        let line_height = size * PT_TO_MM * 1.3;
+        let max_chars = ((self.format.size_mm().0 - 2.0 * MARGIN_MM) / (size * PT_TO_MM * 0.5)) as usize;
+        let indent = text.len() - text.trim_start().len();
+        for line in wrap_text(text, max_chars.saturating_sub(indent)) {
+            if self.y - line_height < MARGIN_MM {
+                self.new_page();
+            }
+            self.y -= line_height;
+            let font = if bold { &self.bold } else { &self.regular };
+            self.layer.use_text(format!("{}{}", " ".repeat(indent), line), size, Mm(MARGIN_MM), Mm(self.y), font);
+        }
+    }
+
+    fn space(&mut self, mm: f32) {
+        self.y -= mm;
+    }
+}
+
+/// Every recipe starts on a new page and continues on the next one when it does not fit.
+pub fn recipes_pdf(title: &str, recipes: &[PrintRecipe], format: EPaperFormat) -> Vec<u8> {
+    let (width, height) = format.size_mm();
+    let (doc, page, layer) = PdfDocument::new(title, Mm(width), Mm(height), "Page 1");
+    let layer = doc.get_page(page).get_layer(layer);
+    let regular = doc.add_builtin_font(BuiltinFont::Helvetica).unwrap();
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).unwrap();
+    let mut writer = PdfWriter { doc, layer, format, regular, bold, y: height - MARGIN_MM, pages: 1 };
+    for (i, cur) in recipes.iter().enumerate() {
+        if i > 0 {
+            writer.new_page();
+        }
+        writer.write(cur.name.as_str(), 16.0, true);
+        writer.write(format!("{} · {}", cur.course, cur.season).as_str(), 9.0, false);
+        if let Some(reference) = cur.reference.as_ref() {
+            writer.write(reference, 9.0, false);
+        }
+        let sections = plain_recipe_text(cur.text.as_str());
+        if !sections.iter().any(|x| x.0 == Some(ESection::Ingredients)) && !cur.ingredients.is_empty() {
+            writer.space(3.0);
+            writer.write("Ingredients", 11.0, true);
+            for ingredient in cur.ingredients.iter() {
+                writer.write(format!("- {}", ingredient).as_str(), 10.0, false);
+            }
+        }
+        for (section, lines) in sections {
+            writer.space(3.0);
+            if let Some(section) = section {
+                writer.write(section.to_string().as_str(), 11.0, true);
+            }
+            for line in lines {
+                writer.write(line.as_str(), 10.0, false);
+            }
+        }
+    }
+    writer.doc.save_to_bytes().unwrap()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::print::{book_reference, recipes_pdf, wrap_text, EPaperFormat, PrintRecipe};
+
+    #[test]
+    fn wraps_words() {
+        assert_eq!(vec!["Fry the", "onions", "until soft"], wrap_text("Fry the onions until soft", 10));
+        assert_eq!(vec!["abcde", "fgh x"], wrap_text("abcdefgh x", 5));
+        assert!(wrap_text("  ", 10).is_empty());
+    }
+
+    #[test]
+    fn references_physical_books() {
+        assert_eq!(Some("Jerusalem, p. 40".to_string()), book_reference(Some("Jerusalem"), Some(40)));
+        assert_eq!(Some("Jerusalem".to_string()), book_reference(Some("Jerusalem"), None));
+        assert_eq!(None, book_reference(None, Some(40)));
+    }
+
+    #[test]
+    fn one_recipe_per_page() {
+        let recipe = PrintRecipe {
+            name: "Rösti".to_string(),
+            course: "Mains".to_string(),
+            season: "Winter".to_string(),
+            reference: book_reference(Some("Jerusalem"), Some(40)),
+            ingredients: vec!["potato".to_string()],
+            text: "Method\nGrate the potatoes.\nFry for 10 minutes.".to_string(),
+        };
+        let long = PrintRecipe { text: "Method\n".to_string() + "Stir.\n".repeat(80).as_str(), ..recipe.clone() };
+        let count_pages = |x: Vec<u8>| String::from_utf8_lossy(&x).matches("/Type/Page/").count();
+        let pdf = recipes_pdf("Test", &[recipe.clone(), recipe.clone()], EPaperFormat::A5);
+        assert!(pdf.starts_with(b"%PDF"));
+        assert_eq!(2, count_pages(pdf));
+        assert_eq!(1, count_pages(recipes_pdf("Test", &[recipe], EPaperFormat::A5)));
+        assert!(count_pages(recipes_pdf("Test", &[long], EPaperFormat::IndexCard)) > 1);
+    }
+}
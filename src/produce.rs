@@ -0,0 +1,124 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt;
+
+/// The calendar seeded by the migrations, used wherever a region has no calendar of its own.
+pub const DEFAULT_REGION: &str = "central-europe";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EHemisphere {
+    North,
+    South,
+}
+
+impl EHemisphere {
+    /// Anything but "south" is the northern hemisphere.
+    pub fn from_code(code: Option<&str>) -> EHemisphere {
+        match code.map(|x| x.trim().to_lowercase()).as_deref() {
+            Some("south") => EHemisphere::South,
+            _ => EHemisphere::North,
+        }
+    }
+
+    /// The month of a northern calendar that matches `month` here, half a year off in the south.
+    pub fn northern_month(&self, month: u32) -> u32 {
+        match self {
+            EHemisphere::North => month,
+            EHemisphere::South => (month + 5) % 12 + 1,
+        }
+    }
+}
+
+impl fmt::Display for EHemisphere {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EHemisphere::North => write!(f, "northern hemisphere"),
+            EHemisphere::South => write!(f, "southern hemisphere"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProduceConfig {
+    pub region: String,
+    pub hemisphere: EHemisphere,
+}
+
+impl ProduceConfig {
+    /// `PRODUCE_REGION` and `HEMISPHERE`, by default the central European calendar.
+    pub fn from_env() -> ProduceConfig {
+        ProduceConfig {
+            region: env::var("PRODUCE_REGION").ok()
+                .map(|x| x.trim().to_lowercase())
+                .filter(|x| !x.is_empty())
+                .unwrap_or(DEFAULT_REGION.to_string()),
+            hemisphere: EHemisphere::from_code(env::var("HEMISPHERE").ok().as_deref()),
+        }
+    }
+}
+
+/// Ingredient ids to the months they are in season.
+pub type ProduceCalendar = HashMap<i32, HashSet<u32>>;
+
+pub fn in_season(calendar: &ProduceCalendar, month: u32) -> HashSet<i32> {
+    calendar.iter()
+        .filter(|(_, months)| months.contains(&month))
+        .map(|(ingredient_id, _)| *ingredient_id)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeasonalScore {
+    pub recipe_id: i32,
+    pub in_season: Vec<i32>,
+    pub total: usize,
+}
+
+impl SeasonalScore {
+    pub fn share(&self) -> f32 {
+        self.in_season.len() as f32 / self.total.max(1) as f32
+    }
+}
+
+/// Recipes with something in season, most in-season ingredients first and then the larger share of them.
+pub fn rank_by_season(recipes_to_ingredients: &HashMap<i32, Vec<i32>>, in_season: &HashSet<i32>) -> Vec<SeasonalScore> {
+    let mut res: Vec<SeasonalScore> = recipes_to_ingredients.iter()
+        .map(|(recipe_id, ingredients)| SeasonalScore {
+            recipe_id: *recipe_id,
+            in_season: ingredients.iter().filter(|x| in_season.contains(x)).copied().collect(),
+            total: ingredients.len(),
+        })
+        .filter(|x| !x.in_season.is_empty())
+        .collect();
+    res.sort_by(|a, b| b.in_season.len().cmp(&a.in_season.len())
+        .then(b.share().total_cmp(&a.share()))
+        .then(a.recipe_id.cmp(&b.recipe_id)));
+    res
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::produce::{in_season, rank_by_season, EHemisphere};
+
+    #[test]
+    fn southern_months_are_shifted() {
+        assert_eq!(7, EHemisphere::North.northern_month(7));
+        assert_eq!(1, EHemisphere::South.northern_month(7));
+        assert_eq!(6, EHemisphere::South.northern_month(12));
+        assert_eq!(EHemisphere::South, EHemisphere::from_code(Some(" South")));
+        assert_eq!(EHemisphere::North, EHemisphere::from_code(None));
+    }
+
+    #[test]
+    fn ranks_by_ingredients_in_season() {
+        let calendar = HashMap::from([(1, HashSet::from([7, 8])), (2, HashSet::from([8])), (3, HashSet::from([1]))]);
+        let current = in_season(&calendar, 8);
+        assert_eq!(HashSet::from([1, 2]), current);
+        let recipes = HashMap::from([(10, vec![1, 4, 5, 6]), (11, vec![1, 2, 4]), (12, vec![1, 4]), (13, vec![3, 4])]);
+        let res: Vec<(i32, usize)> = rank_by_season(&recipes, &current).into_iter().map(|x| (x.recipe_id, x.in_season.len())).collect();
+        assert_eq!(vec![(11, 2), (12, 1), (10, 1)], res);
+    }
+}
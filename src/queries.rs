@@ -1,10 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use diesel::{RunQueryDsl, SqliteConnection};
+use chrono::NaiveDate;
+use diesel::{BoolExpressionMethods, ExpressionMethods, JoinOnDsl, NullableExpressionMethods, QueryDsl, RunQueryDsl, SqliteConnection};
 use itertools::Itertools;
 
 use crate::args::SearchPrefill;
-use crate::models::{FullRecipe, Ingredient, QBook, QCourse, RecipeIngredient, RecipeText};
+use crate::cost::{estimate_cost, CostEstimate, CostInput};
+use crate::diet::{collect_allergens, derive_diets, is_classified, EDiet, EIngredientAttribute, IngredientAttributes, RecipeDiet};
+use crate::produce::{in_season, ProduceCalendar, ProduceConfig, DEFAULT_REGION};
+use crate::rating::RatingSummary;
+use crate::recipe_links::{component_ids, roll_up_components};
+use crate::revision::RecipeSnapshot;
+use crate::seasonality::{recipe_season_entries, Season, SeasonEntry};
+use crate::search::language::ERecipeLanguage;
+use crate::models::{CollectionRecipe, Equipment, FullRecipe, Ingredient, IngredientAttribute, IngredientPrice, Nutrient, QBook, QCollection, QCourse, QSeason, ProduceMonth, RecipeDietOverride, RecipeImage, RecipeIngredient, RecipeLink, RecipeRating, RecipeRevision, RecipeSeason, RecipeTag, RecipeText, Synonym, Tag, User};
 
 pub fn build_search_query(params: &SearchPrefill, user_id: i32) -> String {
     let mut simple_criteria: Vec<String> = vec![];
@@ -61,16 +70,15 @@ WHERE recipe_comment.content LIKE '%{}%')", name_for_real, name_for_real, name_f
 }
 
 fn handle_seasons( params: &SearchPrefill) -> Option<String>{
-    let seasons = vec![params.season1, params.season2, params.season3, params.season4, params.season5];
-    let search_seasons: Vec<String> = seasons.iter().enumerate()
-        .filter(|(_i, x)| x.is_some())
-        .map(|(i, _x)| (i+1).to_string())
+    let search_seasons: Vec<String> = params.seasons.iter()
+        .map(|x| x.to_string())
         .collect();
     if search_seasons.is_empty(){
         return None;
     }
 
-    Some(format!("primary_season IN ({})", search_seasons.join(",")))
+    let search_seasons = search_seasons.join(",");
+    Some(format!("(primary_season IN ({}) OR recipe_id IN (SELECT recipe_id FROM recipe_season WHERE season_id IN ({})))", search_seasons, search_seasons))
 }
 
 pub fn build_index_search_query(ids: Vec<i64>, search_args: &SearchPrefill, user_id: i32) -> String {
@@ -96,6 +104,397 @@ pub fn get_recipe_ids_with_texts() -> String {
     "SELECT DISTINCT recipe_id FROM recipe_text;".to_string()
 }
 
+pub fn query_synonym_groups(con: &mut SqliteConnection) -> Vec<(i32, Vec<String>)> {
+    use crate::schema::synonym::dsl::*;
+    synonym.order((group_id, synonym_id))
+        .load::<Synonym>(con)
+        .unwrap()
+        .into_iter()
+        .map(|x| (x.group_id, x.term))
+        .into_group_map()
+        .into_iter()
+        .sorted_by_key(|x| x.0)
+        .collect()
+}
+
+/// Collections the user owns or that were shared with them, ordered by name.
+pub fn query_accessible_collections(con: &mut SqliteConnection, query_user_id: i32) -> Vec<QCollection> {
+    use crate::schema::collection::dsl::*;
+    use crate::schema::collection_share;
+    let shared_ids = collection_share::table
+        .filter(collection_share::user_id.eq(query_user_id))
+        .select(collection_share::collection_id);
+    collection.filter(user_id.eq(query_user_id).or(collection_id.eq_any(shared_ids.nullable())))
+        .order(collection_name.asc())
+        .load::<QCollection>(con)
+        .unwrap()
+}
+
+pub fn query_recipes_to_tags(con: &mut SqliteConnection) -> HashMap<i32, Vec<String>> {
+    use crate::schema::tag::dsl::*;
+    let id_to_tag: HashMap<i32, String> = tag.load::<Tag>(con)
+        .unwrap()
+        .into_iter()
+        .map(|x| (x.tag_id.unwrap(), x.tag_name))
+        .collect();
+    use crate::schema::recipe_tag::dsl::*;
+    recipe_tag.load::<RecipeTag>(con)
+        .unwrap()
+        .iter()
+        .filter_map(|x| id_to_tag.get(&x.tag_id).map(|y| (x.recipe_id, y.clone())))
+        .sorted()
+        .into_group_map()
+}
+
+pub fn query_ingredient_attributes(con: &mut SqliteConnection) -> HashMap<i32, HashSet<EIngredientAttribute>> {
+    use crate::schema::ingredient_attribute::dsl::*;
+    ingredient_attribute.load::<IngredientAttribute>(con)
+        .unwrap()
+        .into_iter()
+        .filter_map(|x| EIngredientAttribute::from_code(&x.attribute).map(|y| (x.ingredient_id, y)))
+        .into_grouping_map()
+        .collect::<HashSet<EIngredientAttribute>>()
+}
+
+pub fn query_diet_overrides(con: &mut SqliteConnection) -> HashMap<i32, HashMap<EDiet, bool>> {
+    use crate::schema::recipe_diet_override::dsl::*;
+    recipe_diet_override.load::<RecipeDietOverride>(con)
+        .unwrap()
+        .into_iter()
+        .filter_map(|x| EDiet::from_code(&x.diet).map(|y| (x.recipe_id, (y, x.is_diet))))
+        .into_grouping_map()
+        .collect::<HashMap<EDiet, bool>>()
+}
+
+pub fn query_recipe_diets(con: &mut SqliteConnection) -> HashMap<i32, RecipeDiet> {
+    let attributes = query_ingredient_attributes(con);
+    let classified: HashSet<i32> = {
+        use crate::schema::ingredient::dsl::*;
+        ingredient.filter(classified.eq(true)).select(id).load::<Option<i32>>(con).unwrap().into_iter().flatten().collect()
+    };
+    let overrides = query_diet_overrides(con);
+    let recipes_to_attributes: HashMap<i32, Vec<IngredientAttributes>> = query_recipes_to_ingredient_ids(con).into_iter()
+        .map(|(cur_recipe_id, ingredient_ids)| (cur_recipe_id, ingredient_ids.iter()
+            .map(|x| Some(attributes.get(x).cloned().unwrap_or_default()).filter(|_| classified.contains(x)))
+            .collect()))
+        .collect();
+    use crate::schema::recipe::dsl::*;
+    let no_overrides: HashMap<EDiet, bool> = HashMap::new();
+    recipe.select(crate::schema::recipe::recipe_id)
+        .load::<Option<i32>>(con)
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .map(|cur_recipe_id| {
+            let ingredient_attributes = recipes_to_attributes.get(&cur_recipe_id).map(|x| x.as_slice()).unwrap_or(&[]);
+            let diet = RecipeDiet {
+                diets: derive_diets(ingredient_attributes, overrides.get(&cur_recipe_id).unwrap_or(&no_overrides)),
+                allergens: collect_allergens(ingredient_attributes),
+                unknown: !is_classified(ingredient_attributes),
+            };
+            (cur_recipe_id, diet)
+        })
+        .collect()
+}
+
+/// Ingredient ids of every recipe including those of its sub-recipes.
+pub fn query_recipes_to_ingredient_ids(con: &mut SqliteConnection) -> HashMap<i32, Vec<i32>> {
+    use crate::schema::recipe_ingredient::dsl::*;
+    let own: HashMap<i32, Vec<i32>> = recipe_ingredient.load::<RecipeIngredient>(con)
+        .unwrap()
+        .into_iter()
+        .map(|x| (x.recipe_id, x.ingredient_id))
+        .into_group_map();
+    roll_up_components(&own, &query_recipe_links(con))
+}
+
+pub fn query_recipes_to_diets(con: &mut SqliteConnection) -> HashMap<i32, Vec<EDiet>> {
+    query_recipe_diets(con).into_iter()
+        .map(|(cur_recipe_id, diet)| (cur_recipe_id, diet.diets))
+        .collect()
+}
+
+pub fn query_rating_summaries(con: &mut SqliteConnection) -> HashMap<i32, RatingSummary> {
+    use crate::schema::recipe_rating::dsl::*;
+    recipe_rating.load::<RecipeRating>(con)
+        .unwrap()
+        .into_iter()
+        .map(|x| (x.recipe_id, (x.rating, x.cook_again)))
+        .into_group_map()
+        .into_iter()
+        .map(|(cur_recipe_id, entries)| (cur_recipe_id, RatingSummary::summarize(&entries)))
+        .collect()
+}
+
+/// In the order they are listed everywhere.
+pub fn query_seasons(con: &mut SqliteConnection) -> Vec<Season> {
+    use crate::schema::season::dsl::*;
+    season.order((position, season_id))
+        .load::<QSeason>(con)
+        .unwrap()
+        .iter()
+        .map(Season::from)
+        .collect()
+}
+
+pub fn query_season_names(con: &mut SqliteConnection) -> HashMap<i32, String> {
+    query_seasons(con).into_iter()
+        .map(|x| (x.season_id, x.name))
+        .collect()
+}
+
+/// Every recipe with its primary season first, see `recipe_season_entries`.
+pub fn query_recipe_seasons(con: &mut SqliteConnection) -> HashMap<i32, Vec<SeasonEntry>> {
+    let rows: HashMap<i32, Vec<RecipeSeason>> = {
+        use crate::schema::recipe_season::dsl::*;
+        recipe_season.load::<RecipeSeason>(con)
+            .unwrap()
+            .into_iter()
+            .map(|x| (x.recipe_id, x))
+            .into_group_map()
+    };
+    use crate::schema::recipe::dsl::*;
+    recipe.select((recipe_id, primary_season))
+        .load::<(Option<i32>, i32)>(con)
+        .unwrap()
+        .into_iter()
+        .filter_map(|(cur_recipe_id, cur_season)| {
+            let cur_recipe_id = cur_recipe_id?;
+            let cur_rows = rows.get(&cur_recipe_id).map(|x| x.as_slice()).unwrap_or(&[]);
+            Some((cur_recipe_id, recipe_season_entries(cur_season, cur_rows)))
+        })
+        .collect()
+}
+
+pub fn query_produce_calendar(con: &mut SqliteConnection, query_region: &str) -> ProduceCalendar {
+    use crate::schema::produce_month::dsl::*;
+    produce_month.filter(region.eq(query_region))
+        .load::<ProduceMonth>(con)
+        .unwrap()
+        .into_iter()
+        .map(|x| (x.ingredient_id, x.month as u32))
+        .into_grouping_map()
+        .collect::<HashSet<u32>>()
+}
+
+/// Regions without a calendar of their own use the default one, shifted by half a year in the south.
+pub fn query_in_season_ingredients(con: &mut SqliteConnection, config: &ProduceConfig, month: u32) -> HashSet<i32> {
+    let calendar = query_produce_calendar(con, config.region.as_str());
+    if !calendar.is_empty() {
+        return in_season(&calendar, month);
+    }
+    in_season(&query_produce_calendar(con, DEFAULT_REGION), config.hemisphere.northern_month(month))
+}
+
+pub fn query_produce_regions(con: &mut SqliteConnection) -> Vec<String> {
+    use crate::schema::produce_month::dsl::*;
+    produce_month.select(region)
+        .distinct()
+        .order(region)
+        .load::<String>(con)
+        .unwrap()
+}
+
+/// The chosen cover, or the first image when none was picked.
+pub fn query_recipe_covers(con: &mut SqliteConnection) -> HashMap<i32, RecipeImage> {
+    use crate::schema::recipe_image::dsl::*;
+    recipe_image.order((is_cover.desc(), position))
+        .load::<RecipeImage>(con)
+        .unwrap()
+        .into_iter()
+        // later entries overwrite earlier ones when collecting
+        .rev()
+        .map(|x| (x.recipe_id, x))
+        .collect()
+}
+
+/// Ingredient ids to the food of the nutrient table they are mapped to.
+pub fn query_ingredient_nutrients(con: &mut SqliteConnection) -> HashMap<i32, Nutrient> {
+    use crate::schema::ingredient_nutrient;
+    use crate::schema::nutrient;
+    ingredient_nutrient::table.inner_join(nutrient::table)
+        .select((ingredient_nutrient::ingredient_id, nutrient::all_columns))
+        .load::<(i32, Nutrient)>(con)
+        .unwrap()
+        .into_iter()
+        .collect()
+}
+
+/// The newest price of every ingredient that has one.
+pub fn query_ingredient_prices(con: &mut SqliteConnection) -> HashMap<i32, IngredientPrice> {
+    use crate::schema::ingredient_price::dsl::*;
+    ingredient_price.order((priced_on, price_id))
+        .load::<IngredientPrice>(con)
+        .unwrap()
+        .into_iter()
+        .map(|x| (x.ingredient_id, x))
+        .collect()
+}
+
+/// Estimated cost of every recipe including its components, from the gram amounts of the nutrition estimate.
+pub fn query_recipe_costs(con: &mut SqliteConnection, today: NaiveDate) -> HashMap<i32, CostEstimate> {
+    let prices = query_ingredient_prices(con);
+    let rows: HashMap<i32, Vec<CostInput>> = {
+        use crate::schema::recipe_ingredient::dsl::*;
+        use crate::schema::ingredient::dsl::*;
+        recipe_ingredient.inner_join(ingredient.on(id.eq(ingredient_id.nullable())))
+            .select((recipe_id, ingredient_id, name, amount_grams))
+            .load::<(i32, i32, Option<String>, Option<f32>)>(con)
+            .unwrap()
+            .into_iter()
+            .map(|(cur_recipe_id, cur_ingredient_id, cur_name, grams)| (cur_recipe_id, CostInput { name: cur_name.unwrap_or_default(), amount_grams: grams, price: prices.get(&cur_ingredient_id).cloned() }))
+            .into_group_map()
+    };
+    let links = query_recipe_links(con);
+    use crate::schema::recipe::dsl::*;
+    recipe.select((recipe_id, servings))
+        .load::<(Option<i32>, Option<i32>)>(con)
+        .unwrap()
+        .into_iter()
+        .filter_map(|(cur_recipe_id, cur_servings)| {
+            let cur_recipe_id = cur_recipe_id?;
+            let inputs: Vec<CostInput> = std::iter::once(cur_recipe_id)
+                .chain(component_ids(cur_recipe_id, &links))
+                .flat_map(|x| rows.get(&x).cloned().unwrap_or_default())
+                .collect();
+            Some((cur_recipe_id, estimate_cost(&inputs, cur_servings, today)))
+        })
+        .collect()
+}
+
+/// Estimated cost of one recipe including its components.
+pub fn query_recipe_cost(con: &mut SqliteConnection, query_recipe_id: i32, today: NaiveDate) -> CostEstimate {
+    let prices = query_ingredient_prices(con);
+    let recipe_ids: Vec<i32> = std::iter::once(query_recipe_id)
+        .chain(component_ids(query_recipe_id, &query_recipe_links(con)))
+        .collect();
+    let rows: HashMap<i32, Vec<CostInput>> = {
+        use crate::schema::recipe_ingredient::dsl::*;
+        use crate::schema::ingredient::dsl::*;
+        recipe_ingredient.inner_join(ingredient.on(id.eq(ingredient_id.nullable())))
+            .filter(recipe_id.eq_any(&recipe_ids))
+            .select((recipe_id, ingredient_id, name, amount_grams))
+            .load::<(i32, i32, Option<String>, Option<f32>)>(con)
+            .unwrap()
+            .into_iter()
+            .map(|(cur_recipe_id, cur_ingredient_id, cur_name, grams)| (cur_recipe_id, CostInput { name: cur_name.unwrap_or_default(), amount_grams: grams, price: prices.get(&cur_ingredient_id).cloned() }))
+            .into_group_map()
+    };
+    let cur_servings = {
+        use crate::schema::recipe::dsl::*;
+        recipe.filter(recipe_id.eq(query_recipe_id))
+            .select(servings)
+            .first::<Option<i32>>(con)
+            .unwrap()
+    };
+    let inputs: Vec<CostInput> = recipe_ids.iter()
+        .flat_map(|x| rows.get(x).cloned().unwrap_or_default())
+        .collect();
+    estimate_cost(&inputs, cur_servings, today)
+}
+
+/// All links between recipes, few enough to be resolved in memory.
+pub fn query_recipe_links(con: &mut SqliteConnection) -> Vec<RecipeLink> {
+    use crate::schema::recipe_link::dsl::*;
+    recipe_link.load::<RecipeLink>(con).unwrap()
+}
+
+pub fn query_equipment(con: &mut SqliteConnection) -> Vec<Equipment> {
+    use crate::schema::equipment::dsl::*;
+    equipment.order(name).load::<Equipment>(con).unwrap()
+}
+
+/// Equipment needed for a component is needed for the recipes using it as well.
+pub fn query_recipes_to_equipment_ids(con: &mut SqliteConnection) -> HashMap<i32, Vec<i32>> {
+    use crate::schema::recipe_equipment::dsl::*;
+    let own: HashMap<i32, Vec<i32>> = recipe_equipment.select((recipe_id, equipment_id))
+        .load::<(i32, i32)>(con)
+        .unwrap()
+        .into_iter()
+        .into_group_map();
+    roll_up_components(&own, &query_recipe_links(con))
+}
+
+pub fn query_user_equipment_ids(con: &mut SqliteConnection, query_user_id: i32) -> HashSet<i32> {
+    use crate::schema::user_equipment::dsl::*;
+    user_equipment.filter(user_id.eq(query_user_id))
+        .select(equipment_id)
+        .load::<i32>(con)
+        .unwrap()
+        .into_iter()
+        .collect()
+}
+
+pub fn query_recipe_names(con: &mut SqliteConnection) -> HashMap<i32, String> {
+    use crate::schema::recipe::dsl::*;
+    recipe.select((recipe_id, recipe_name))
+        .load::<(Option<i32>, Option<String>)>(con)
+        .unwrap()
+        .into_iter()
+        .filter_map(|(cur_id, cur_name)| Some((cur_id?, cur_name.unwrap_or_default())))
+        .collect()
+}
+
+/// The current state of a recipe in the form it is kept in its revisions.
+pub fn query_recipe_snapshot(con: &mut SqliteConnection, query_recipe_id: i32) -> Option<RecipeSnapshot> {
+    let cur_recipe = {
+        use crate::schema::recipe::dsl::*;
+        recipe.filter(recipe_id.eq(query_recipe_id)).first::<FullRecipe>(con).ok()?
+    };
+    let ingredient_ids: Vec<i32> = {
+        use crate::schema::recipe_ingredient::dsl::*;
+        recipe_ingredient.filter(recipe_id.eq(query_recipe_id)).select(ingredient_id).load::<i32>(con).unwrap()
+    };
+    let ingredient_names: Vec<String> = {
+        use crate::schema::ingredient::dsl::*;
+        ingredient.filter(id.eq_any(ingredient_ids)).select(name).load::<Option<String>>(con).unwrap()
+    }.into_iter().flatten().sorted().collect();
+    let text = {
+        use crate::schema::recipe_text::dsl::*;
+        recipe_text.filter(recipe_id.eq(query_recipe_id)).first::<RecipeText>(con).ok()
+    };
+    Some(RecipeSnapshot {
+        recipe_name: cur_recipe.recipe_name,
+        primary_season: cur_recipe.primary_season,
+        course_id: cur_recipe.course_id,
+        book_id: cur_recipe.book_id,
+        page: cur_recipe.page,
+        recipe_url: cur_recipe.recipe_url,
+        language: cur_recipe.language,
+        ingredients: ingredient_names,
+        tags: query_recipes_to_tags(con).remove(&query_recipe_id).unwrap_or_default(),
+        content: text.map(|x| x.content).unwrap_or_default(),
+        servings: cur_recipe.servings,
+        prep_minutes: cur_recipe.prep_minutes,
+        cook_minutes: cur_recipe.cook_minutes,
+        total_minutes: cur_recipe.total_minutes,
+        difficulty: cur_recipe.difficulty,
+    })
+}
+
+/// Oldest first.
+pub fn query_revisions(con: &mut SqliteConnection, query_recipe_id: i32) -> Vec<RecipeRevision> {
+    use crate::schema::recipe_revision::dsl::*;
+    recipe_revision.filter(recipe_id.eq(query_recipe_id))
+        .order(revision_id)
+        .load::<RecipeRevision>(con)
+        .unwrap()
+}
+
+/// Users without a display name are shown by the part of their email before the @.
+pub fn query_display_names(con: &mut SqliteConnection) -> HashMap<i32, String> {
+    use crate::schema::user::dsl::*;
+    user.load::<User>(con)
+        .unwrap()
+        .into_iter()
+        .map(|x| {
+            let name = x.display_name.filter(|y| !y.trim().is_empty())
+                .unwrap_or(x.email.split('@').next().unwrap_or_default().to_string());
+            (x.id.unwrap(), name)
+        })
+        .collect()
+}
+
 pub fn query_all_recipes(con: &mut SqliteConnection) -> Vec<RecipeQueryResult> {
     use crate::schema::recipe::dsl::*;
 
@@ -106,14 +505,9 @@ pub fn query_all_recipes(con: &mut SqliteConnection) -> Vec<RecipeQueryResult> {
         .iter()
         .map(|x| (x.id.unwrap(), x.name.clone().unwrap()))
         .collect();
-    use crate::schema::recipe_ingredient::dsl::*;
-    let recipes_to_ingredients: HashMap<i32, Vec<String>> = recipe_ingredient.load::<RecipeIngredient>(con)
-        .unwrap()
-        .iter()
-        .map(|x| (x.recipe_id, id_to_ingredients.get(&x.ingredient_id)))
-        .filter(|x| x.clone().1.is_some())
-        .map(|x| (x.clone().0, x.clone().1.unwrap().clone()))
-        .into_group_map();
+    let recipes_to_ingredients: HashMap<i32, Vec<String>> = query_recipes_to_ingredient_ids(con).into_iter()
+        .map(|(cur_recipe_id, ingredient_ids)| (cur_recipe_id, ingredient_ids.iter().filter_map(|x| id_to_ingredients.get(x).cloned()).collect()))
+        .collect();
 
     use crate::schema::recipe_text::dsl::*;
     let ids_to_texts: HashMap<i32, String> = recipe_text.load::<RecipeText>(con)
@@ -128,17 +522,38 @@ pub fn query_all_recipes(con: &mut SqliteConnection) -> Vec<RecipeQueryResult> {
     let book_id_to_name: HashMap<i32, String> = _books.iter()
         .map(|x| (x.clone().book_id.unwrap(), x.clone().book_name.unwrap()))
         .collect();
+    let book_id_to_language: HashMap<i32, String> = _books.iter()
+        .filter(|x| x.language.is_some())
+        .map(|x| (x.book_id.unwrap(), x.language.clone().unwrap()))
+        .collect();
 
     let courses: Vec<QCourse> = course.load::<QCourse>(con).unwrap();
     let course_id_to_name: HashMap<i32, String> = courses.iter()
         .map(|x| (x.course_id.unwrap(), x.course_name.as_ref().unwrap().clone()))
         .collect();
+    use crate::schema::collection_recipe::dsl::*;
+    let recipes_to_collections: HashMap<i32, Vec<i32>> = collection_recipe.load::<CollectionRecipe>(con)
+        .unwrap()
+        .iter()
+        .map(|x| (x.recipe_id, x.collection_id))
+        .into_group_map();
+    let lookups = RecipeLookups {
+        recipes_to_ingredients,
+        ids_to_texts,
+        course_id_to_name,
+        book_id_to_name,
+        book_id_to_language,
+        recipes_to_collections,
+        recipes_to_tags: query_recipes_to_tags(con),
+        recipe_diets: query_recipe_diets(con),
+        recipe_seasons: query_recipe_seasons(con),
+    };
     /*
     let recipe_texts: HashMap<i32, RecipeText> = recipe_text.load::<RecipeText>(con)
         .unwrap().iter().map(|x| (x.recipe_id, x.clone())).collect();
 */
     let olol: Vec<RecipeQueryResult> = recipes.iter()
-        .map(|x| map_recipe_and_ingredient(x, &recipes_to_ingredients, &ids_to_texts, &course_id_to_name, &book_id_to_name))
+        .map(|x| map_recipe_and_ingredient(x, &lookups))
         .collect();
     olol
 }
@@ -150,6 +565,11 @@ pub struct RecipeQueryResult {
     pub comments: Vec<String>,
     pub course_name: String,
     pub book_name: Option<String>,
+    pub language: ERecipeLanguage,
+    pub collection_ids: Vec<i32>,
+    pub tags: Vec<String>,
+    pub diets: Vec<EDiet>,
+    pub season_ids: Vec<i32>,
 }
 
 /*
@@ -166,17 +586,25 @@ pub fn query_all_courses(con: &mut LoggingConnection<SqliteConnection>){
 
  */
 
-fn map_recipe_and_ingredient(x: &FullRecipe, recipes_to_ingredients: &HashMap<i32, Vec<String>>, ids_to_texts: &HashMap<i32, String>,
-                             course_id_to_name: &HashMap<i32, String>, book_id_to_name: &HashMap<i32, String>,
-) -> RecipeQueryResult {
-    let ingredients = if recipes_to_ingredients.get(&x.recipe_id.unwrap()).is_none() {
-        vec![]
-    } else {
-        recipes_to_ingredients.get(&x.recipe_id.unwrap()).unwrap().clone()
-    };
-    let course_name = course_id_to_name.get(&x.course_id).unwrap();
-    let book_name = x.book_id.map(|y| book_id_to_name.get(&y)).flatten().map(|x| x.clone());
-    let text = ids_to_texts.get(&x.recipe_id.unwrap());
+/// Everything [`query_all_recipes`] looks up by recipe, book or course id.
+struct RecipeLookups {
+    recipes_to_ingredients: HashMap<i32, Vec<String>>,
+    ids_to_texts: HashMap<i32, String>,
+    course_id_to_name: HashMap<i32, String>,
+    book_id_to_name: HashMap<i32, String>,
+    book_id_to_language: HashMap<i32, String>,
+    recipes_to_collections: HashMap<i32, Vec<i32>>,
+    recipes_to_tags: HashMap<i32, Vec<String>>,
+    recipe_diets: HashMap<i32, RecipeDiet>,
+    recipe_seasons: HashMap<i32, Vec<SeasonEntry>>,
+}
+
+fn map_recipe_and_ingredient(x: &FullRecipe, lookups: &RecipeLookups) -> RecipeQueryResult {
+    let ingredients = lookups.recipes_to_ingredients.get(&x.recipe_id.unwrap()).cloned().unwrap_or_default();
+    let course_name = lookups.course_id_to_name.get(&x.course_id).unwrap();
+    let book_name = x.book_id.and_then(|y| lookups.book_id_to_name.get(&y)).cloned();
+    let text = lookups.ids_to_texts.get(&x.recipe_id.unwrap());
+    let language = ERecipeLanguage::resolve(x.language.as_ref(), x.book_id.and_then(|y| lookups.book_id_to_language.get(&y)));
 
 
     RecipeQueryResult {
@@ -186,5 +614,12 @@ fn map_recipe_and_ingredient(x: &FullRecipe, recipes_to_ingredients: &HashMap<i3
         comments: vec![],
         course_name: course_name.clone(),
         book_name,
+        language,
+        collection_ids: lookups.recipes_to_collections.get(&x.recipe_id.unwrap()).cloned().unwrap_or_default(),
+        tags: lookups.recipes_to_tags.get(&x.recipe_id.unwrap()).cloned().unwrap_or_default(),
+        diets: lookups.recipe_diets.get(&x.recipe_id.unwrap()).map(|y| y.diets.clone()).unwrap_or_default(),
+        season_ids: lookups.recipe_seasons.get(&x.recipe_id.unwrap())
+            .map(|y| y.iter().map(|z| z.season_id).collect())
+            .unwrap_or_else(|| vec![x.primary_season]),
     }
 }
@@ -0,0 +1,71 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RatingSummary {
+    pub average: Option<f32>,
+    pub count: usize,
+    pub cook_again: usize,
+}
+
+impl RatingSummary {
+    /// Entries are `(rating, cook_again)` per user, a user may tick "cook again" without giving stars.
+    pub fn summarize(entries: &[(Option<i32>, bool)]) -> RatingSummary {
+        let ratings: Vec<i32> = entries.iter().filter_map(|x| x.0).collect();
+        RatingSummary {
+            average: Some(ratings.len()).filter(|x| *x > 0).map(|x| ratings.iter().sum::<i32>() as f32 / x as f32),
+            count: ratings.len(),
+            cook_again: entries.iter().filter(|x| x.1).count(),
+        }
+    }
+}
+
+impl fmt::Display for RatingSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.average {
+            Some(average) => write!(f, "★ {:.1} ({})", average, self.count),
+            None => write!(f, "-"),
+        }
+    }
+}
+
+/// Best rated first, more ratings break ties, unrated recipes go last.
+pub fn rating_order(a: Option<&RatingSummary>, b: Option<&RatingSummary>) -> Ordering {
+    let a_average = a.and_then(|x| x.average);
+    let b_average = b.and_then(|x| x.average);
+    match (a_average, b_average) {
+        (Some(x), Some(y)) => y.total_cmp(&x).then_with(|| b.unwrap().count.cmp(&a.unwrap().count)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use crate::rating::{rating_order, RatingSummary};
+
+    #[test]
+    fn summarize_skips_missing_stars() {
+        let res = RatingSummary::summarize(&[(Some(4), true), (None, true), (Some(5), false)]);
+        assert_eq!(Some(4.5), res.average);
+        assert_eq!(2, res.count);
+        assert_eq!(2, res.cook_again);
+        assert_eq!("★ 4.5 (2)", res.to_string());
+        assert_eq!(None, RatingSummary::summarize(&[(None, true)]).average);
+    }
+
+    #[test]
+    fn unrated_last() {
+        let good = RatingSummary::summarize(&[(Some(5), false)]);
+        let popular = RatingSummary::summarize(&[(Some(5), false), (Some(5), false)]);
+        let cook_again_only = RatingSummary::summarize(&[(None, true)]);
+        assert_eq!(Ordering::Less, rating_order(Some(&popular), Some(&good)));
+        assert_eq!(Ordering::Less, rating_order(Some(&good), Some(&cook_again_only)));
+        assert_eq!(Ordering::Greater, rating_order(None, Some(&good)));
+        assert_eq!(Ordering::Equal, rating_order(None, Some(&cook_again_only)));
+    }
+}
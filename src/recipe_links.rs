@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+
+use itertools::Itertools;
+
+use crate::markdown::ESection;
+use crate::models::RecipeLink;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ELinkType {
+    VariantOf,
+    ServeWith,
+    Component,
+}
+
+impl ELinkType {
+    pub fn get_link_types() -> Vec<ELinkType> {
+        vec![ELinkType::ServeWith, ELinkType::VariantOf, ELinkType::Component]
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ELinkType::VariantOf => "variant_of",
+            ELinkType::ServeWith => "serve_with",
+            ELinkType::Component => "component",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<ELinkType> {
+        ELinkType::get_link_types().into_iter().find(|x| x.code() == code)
+    }
+
+    /// Heading of the linked recipes, seen from the recipe the link starts at or from the other end.
+    pub fn label(&self, outgoing: bool) -> &'static str {
+        match (self, outgoing) {
+            (ELinkType::VariantOf, true) => "Variant of",
+            (ELinkType::VariantOf, false) => "Variants",
+            (ELinkType::ServeWith, _) => "Goes well with",
+            (ELinkType::Component, true) => "Components",
+            (ELinkType::Component, false) => "Used in",
+        }
+    }
+}
+
+impl fmt::Display for ELinkType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ELinkType::VariantOf => write!(f, "is a variant of"),
+            ELinkType::ServeWith => write!(f, "is served with"),
+            ELinkType::Component => write!(f, "uses as a component"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct LinkedRecipe {
+    pub link_type: ELinkType,
+    pub recipe_id: i32,
+    pub outgoing: bool,
+}
+
+/// Links of a recipe in both directions grouped by heading, rows with unknown link types are ignored.
+pub fn linked_recipes(recipe_id: i32, rows: &[RecipeLink]) -> Vec<(&'static str, Vec<LinkedRecipe>)> {
+    rows.iter()
+        .filter_map(|x| {
+            let link_type = ELinkType::from_code(x.link_type.as_str())?;
+            match (x.recipe_id == recipe_id, x.linked_recipe_id == recipe_id) {
+                (true, _) => Some(LinkedRecipe { link_type, recipe_id: x.linked_recipe_id, outgoing: true }),
+                (_, true) => Some(LinkedRecipe { link_type, recipe_id: x.recipe_id, outgoing: link_type == ELinkType::ServeWith }),
+                _ => None,
+            }
+        })
+        .unique()
+        .sorted_by_key(|x| (ELinkType::get_link_types().iter().position(|y| *y == x.link_type), !x.outgoing, x.recipe_id))
+        .group_by(|x| x.link_type.label(x.outgoing))
+        .into_iter()
+        .map(|(label, group)| (label, group.collect()))
+        .collect()
+}
+
+/// Recipes served with any of `recipe_ids` that are not among them, the most often linked first.
+pub fn accompaniments(recipe_ids: &HashSet<i32>, rows: &[RecipeLink]) -> Vec<i32> {
+    let mut counts: HashMap<i32, usize> = HashMap::new();
+    for row in rows.iter().filter(|x| ELinkType::from_code(x.link_type.as_str()) == Some(ELinkType::ServeWith)) {
+        for (from, to) in [(row.recipe_id, row.linked_recipe_id), (row.linked_recipe_id, row.recipe_id)] {
+            if recipe_ids.contains(&from) && !recipe_ids.contains(&to) {
+                *counts.entry(to).or_insert(0) += 1;
+            }
+        }
+    }
+    counts.into_iter()
+        .sorted_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)))
+        .map(|(recipe_id, _)| recipe_id)
+        .collect()
+}
+
+/// Components of components are included, each once in the order they are reached.
+/// Loops that are already stored do not hang, they are only rejected when saving.
+pub fn component_ids(recipe_id: i32, rows: &[RecipeLink]) -> Vec<i32> {
+    let mut res: Vec<i32> = vec![];
+    let mut pending = vec![recipe_id];
+    while let Some(cur) = pending.pop() {
+        let direct: Vec<i32> = rows.iter()
+            .filter(|x| x.recipe_id == cur && ELinkType::from_code(x.link_type.as_str()) == Some(ELinkType::Component))
+            .map(|x| x.linked_recipe_id)
+            .filter(|x| *x != recipe_id && !res.contains(x))
+            .collect();
+        res.extend(direct.iter());
+        pending.extend(direct.iter().rev());
+    }
+    res
+}
+
+/// Recipes that use `recipe_id` directly or as part of another component.
+pub fn used_in_ids(recipe_id: i32, rows: &[RecipeLink]) -> Vec<i32> {
+    rows.iter()
+        .map(|x| x.recipe_id)
+        .unique()
+        .filter(|x| *x != recipe_id && component_ids(*x, rows).contains(&recipe_id))
+        .collect()
+}
+
+/// Whether using `component_id` in `recipe_id` would make the recipe contain itself.
+pub fn creates_cycle(recipe_id: i32, component_id: i32, rows: &[RecipeLink]) -> bool {
+    recipe_id == component_id || component_ids(component_id, rows).contains(&recipe_id)
+}
+
+/// Every recipe with the ingredients (or equipment) of its components added, each value once.
+pub fn roll_up_components<T: Clone + Eq + Hash>(recipes_to_values: &HashMap<i32, Vec<T>>, rows: &[RecipeLink]) -> HashMap<i32, Vec<T>> {
+    let mut res = recipes_to_values.clone();
+    for cur_recipe_id in rows.iter().map(|x| x.recipe_id).unique() {
+        let rolled_up: Vec<T> = std::iter::once(cur_recipe_id)
+            .chain(component_ids(cur_recipe_id, rows))
+            .flat_map(|x| recipes_to_values.get(&x).cloned().unwrap_or_default())
+            .unique()
+            .collect();
+        if !rolled_up.is_empty() {
+            res.insert(cur_recipe_id, rolled_up);
+        }
+    }
+    res
+}
+
+/// A sub-recipe as shown inline on the recipe that uses it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentRecipe {
+    pub recipe_id: i32,
+    pub name: String,
+    pub ingredients: Vec<String>,
+    pub sections: Vec<(Option<ESection>, String)>,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::models::RecipeLink;
+    use crate::recipe_links::{accompaniments, component_ids, creates_cycle, linked_recipes, roll_up_components, used_in_ids, ELinkType, LinkedRecipe};
+
+    fn row(recipe_id: i32, linked_recipe_id: i32, link_type: &str) -> RecipeLink {
+        RecipeLink { recipe_id, linked_recipe_id, link_type: link_type.to_string(), created_at: None }
+    }
+
+    #[test]
+    fn links_go_both_ways() {
+        let rows = vec![row(1, 2, "serve_with"), row(3, 1, "serve_with"), row(1, 4, "component"), row(5, 1, "variant_of"), row(5, 1, "unknown"), row(6, 7, "component")];
+        let res = linked_recipes(1, &rows);
+        assert_eq!(vec!["Goes well with", "Variants", "Components"], res.iter().map(|x| x.0).collect::<Vec<&str>>());
+        assert_eq!(vec![2, 3], res[0].1.iter().map(|x| x.recipe_id).collect::<Vec<i32>>());
+        assert_eq!(vec![("Used in", vec![LinkedRecipe { link_type: ELinkType::Component, recipe_id: 1, outgoing: false }])], linked_recipes(4, &rows));
+        assert!(linked_recipes(8, &rows).is_empty());
+    }
+
+    #[test]
+    fn suggests_sides_of_results() {
+        let rows = vec![row(1, 10, "serve_with"), row(11, 2, "serve_with"), row(2, 10, "serve_with"), row(1, 2, "serve_with"), row(1, 12, "variant_of")];
+        assert_eq!(vec![10, 11], accompaniments(&HashSet::from([1, 2]), &rows));
+        assert!(accompaniments(&HashSet::new(), &rows).is_empty());
+    }
+
+    #[test]
+    fn components_are_nested() {
+        // lasagne uses ragu and bechamel, bechamel uses roux
+        let rows = vec![row(1, 2, "component"), row(1, 3, "component"), row(3, 4, "component"), row(1, 5, "serve_with")];
+        assert_eq!(vec![2, 3, 4], component_ids(1, &rows));
+        assert_eq!(vec![1, 3], used_in_ids(4, &rows));
+        let ingredients = HashMap::from([(1, vec![10]), (2, vec![20, 10]), (4, vec![40]), (5, vec![50])]);
+        let res = roll_up_components(&ingredients, &rows);
+        assert_eq!(vec![10, 20, 40], res[&1]);
+        assert_eq!(vec![40], res[&3]);
+        assert_eq!(vec![50], res[&5]);
+    }
+
+    #[test]
+    fn rejects_cycles() {
+        let rows = vec![row(1, 2, "component"), row(2, 3, "component"), row(3, 1, "serve_with")];
+        assert!(creates_cycle(3, 1, &rows));
+        assert!(creates_cycle(2, 2, &rows));
+        assert!(!creates_cycle(1, 3, &rows));
+        let stored_loop = vec![row(1, 2, "component"), row(2, 1, "component")];
+        assert_eq!(vec![2], component_ids(1, &stored_loop));
+    }
+}
@@ -0,0 +1,216 @@
+use std::fmt;
+
+use serde_json::Value;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EDifficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl EDifficulty {
+    pub fn get_difficulties() -> Vec<EDifficulty> {
+        vec![EDifficulty::Easy, EDifficulty::Medium, EDifficulty::Hard]
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            EDifficulty::Easy => "easy",
+            EDifficulty::Medium => "medium",
+            EDifficulty::Hard => "hard",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<EDifficulty> {
+        EDifficulty::get_difficulties().into_iter().find(|x| x.code() == code)
+    }
+
+    /// Stored in the index, so a range query finds everything up to a difficulty.
+    pub fn rank(&self) -> i64 {
+        match self {
+            EDifficulty::Easy => 1,
+            EDifficulty::Medium => 2,
+            EDifficulty::Hard => 3,
+        }
+    }
+}
+
+impl fmt::Display for EDifficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EDifficulty::Easy => write!(f, "Easy"),
+            EDifficulty::Medium => write!(f, "Medium"),
+            EDifficulty::Hard => write!(f, "Hard"),
+        }
+    }
+}
+
+/// The total time if it was given, otherwise preparation and cooking added up.
+pub fn effective_minutes(prep_minutes: Option<i32>, cook_minutes: Option<i32>, total_minutes: Option<i32>) -> Option<i32> {
+    total_minutes.or_else(|| match (prep_minutes, cook_minutes) {
+        (None, None) => None,
+        (prep, cook) => Some(prep.unwrap_or(0) + cook.unwrap_or(0)),
+    })
+}
+
+pub fn format_minutes(minutes: i32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} min", m),
+        (h, 0) => format!("{} h", h),
+        (h, m) => format!("{} h {} min", h, m),
+    }
+}
+
+/// For the detail page, like "10 min prep, 25 min cooking, 35 min in total", empty if no time is known.
+pub fn describe_times(prep_minutes: Option<i32>, cook_minutes: Option<i32>, total_minutes: Option<i32>) -> String {
+    let Some(total) = effective_minutes(prep_minutes, cook_minutes, total_minutes) else {
+        return String::new();
+    };
+    [prep_minutes.map(|x| format!("{} prep", format_minutes(x))), cook_minutes.map(|x| format!("{} cooking", format_minutes(x)))].into_iter()
+        .flatten()
+        .chain(std::iter::once(format!("{} in total", format_minutes(total))))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Minutes of an ISO 8601 duration like `PT1H30M`, as used by schema.org. Seconds are rounded up,
+/// years and months are refused since their length is unknown.
+pub fn parse_iso_duration(value: &str) -> Option<i32> {
+    let value = value.trim().to_uppercase();
+    let rest = value.strip_prefix('P')?;
+    let (date, time) = rest.split_once('T').unwrap_or((rest, ""));
+    let mut minutes = 0.0;
+    let mut found = false;
+    for (part, in_time) in [(date, false), (time, true)] {
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() || c == '.' || c == ',' {
+                number.push(c);
+                continue;
+            }
+            let factor = match (in_time, c) {
+                (false, 'W') => 7.0 * 24.0 * 60.0,
+                (false, 'D') => 24.0 * 60.0,
+                (true, 'H') => 60.0,
+                (true, 'M') => 1.0,
+                (true, 'S') => 1.0 / 60.0,
+                _ => return None,
+            };
+            minutes += number.replace(',', ".").parse::<f64>().ok()? * factor;
+            number.clear();
+            found = true;
+        }
+        if !number.is_empty() {
+            return None;
+        }
+    }
+    Some(minutes.ceil() as i32).filter(|_| found)
+}
+
+/// A whole number typed into a form, anything else counts as left empty.
+pub fn parse_count(value: Option<&str>) -> Option<i32> {
+    value?.trim().parse::<i32>().ok().filter(|x| *x >= 0)
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecipeTimes {
+    pub prep_minutes: Option<i32>,
+    pub cook_minutes: Option<i32>,
+    pub total_minutes: Option<i32>,
+    pub servings: Option<i32>,
+}
+
+impl RecipeTimes {
+    /// Fields left empty are taken from `other`.
+    pub fn or(self, other: RecipeTimes) -> RecipeTimes {
+        RecipeTimes {
+            prep_minutes: self.prep_minutes.or(other.prep_minutes),
+            cook_minutes: self.cook_minutes.or(other.cook_minutes),
+            total_minutes: self.total_minutes.or(other.total_minutes),
+            servings: self.servings.or(other.servings),
+        }
+    }
+}
+
+/// Times and yield of the first schema.org `Recipe` in a JSON-LD block, which may still be wrapped in its script tag.
+/// Lists and `@graph` are searched as well.
+pub fn times_from_json_ld(json_ld: &str) -> Option<RecipeTimes> {
+    let start = json_ld.find(['{', '['])?;
+    let end = json_ld.rfind(['}', ']'])?;
+    let value: Value = serde_json::from_str(json_ld.get(start..=end)?).ok()?;
+    let found = find_recipe(&value)?;
+    let minutes = |key: &str| found.get(key).and_then(Value::as_str).and_then(parse_iso_duration);
+    Some(RecipeTimes {
+        prep_minutes: minutes("prepTime"),
+        cook_minutes: minutes("cookTime"),
+        total_minutes: minutes("totalTime"),
+        servings: parse_yield(found.get("recipeYield")).filter(|x| *x > 0),
+    })
+}
+
+fn find_recipe(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(items) => items.iter().find_map(find_recipe),
+        Value::Object(map) if is_recipe(map.get("@type")) => Some(value),
+        Value::Object(map) => map.get("@graph").and_then(find_recipe),
+        _ => None,
+    }
+}
+
+fn is_recipe(type_value: Option<&Value>) -> bool {
+    match type_value {
+        Some(Value::String(x)) => x == "Recipe",
+        Some(Value::Array(items)) => items.iter().any(|x| x.as_str() == Some("Recipe")),
+        _ => false,
+    }
+}
+
+/// `recipeYield` is a number, a text like "4 servings" or a list of those.
+fn parse_yield(value: Option<&Value>) -> Option<i32> {
+    match value? {
+        Value::Number(x) => x.as_f64().map(|x| x.round() as i32),
+        Value::String(x) => x.split(|c: char| !c.is_ascii_digit()).find(|x| !x.is_empty())?.parse().ok(),
+        Value::Array(items) => items.iter().find_map(|x| parse_yield(Some(x))),
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::recipe_time::{describe_times, effective_minutes, parse_iso_duration, times_from_json_ld, RecipeTimes};
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(Some(90), parse_iso_duration("PT1H30M"));
+        assert_eq!(Some(45), parse_iso_duration(" pt45m"));
+        assert_eq!(Some(1), parse_iso_duration("PT20S"));
+        assert_eq!(Some(1530), parse_iso_duration("P1DT1H30M"));
+        assert_eq!(Some(90), parse_iso_duration("PT1.5H"));
+        assert_eq!(None, parse_iso_duration("P1M"));
+        assert_eq!(None, parse_iso_duration("PT"));
+        assert_eq!(None, parse_iso_duration("30 minutes"));
+        assert_eq!(Some(40), effective_minutes(Some(10), Some(30), None));
+        assert_eq!(Some(35), effective_minutes(Some(10), Some(30), Some(35)));
+        assert_eq!(None, effective_minutes(None, None, None));
+        assert_eq!("10 min prep, 1 h 5 min cooking, 1 h 15 min in total", describe_times(Some(10), Some(65), None));
+        assert_eq!("2 h in total", describe_times(None, None, Some(120)));
+        assert_eq!("", describe_times(None, None, None));
+    }
+
+    #[test]
+    fn reads_recipe_from_json_ld() {
+        let json_ld = r#"<script type="application/ld+json">{"@context": "https://schema.org", "@graph": [
+            {"@type": "WebPage", "name": "Shakshuka"},
+            {"@type": ["Recipe"], "prepTime": "PT10M", "cookTime": "PT25M", "recipeYield": ["4", "4 servings"]}
+        ]}</script>"#;
+        let res = times_from_json_ld(json_ld).unwrap();
+        assert_eq!(RecipeTimes { prep_minutes: Some(10), cook_minutes: Some(25), total_minutes: None, servings: Some(4) }, res);
+        let typed = RecipeTimes { total_minutes: Some(30), ..Default::default() };
+        assert_eq!(Some(30), typed.or(res).total_minutes);
+        assert_eq!(Some(6), times_from_json_ld(r#"[{"@type": "Recipe", "recipeYield": "Serves 6"}]"#).unwrap().servings);
+        assert_eq!(None, times_from_json_ld(r#"{"@type": "Article"}"#));
+        assert_eq!(None, times_from_json_ld("not json"));
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use crate::models::RecipeRevision;
+use crate::recipe_time::EDifficulty;
+
+/// Everything an edit can change, ingredients and tags are kept sorted so unchanged lists compare equal.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RecipeSnapshot {
+    pub recipe_name: Option<String>,
+    pub primary_season: i32,
+    pub course_id: i32,
+    pub book_id: Option<i32>,
+    pub page: Option<i32>,
+    pub recipe_url: Option<String>,
+    pub language: Option<String>,
+    pub ingredients: Vec<String>,
+    pub tags: Vec<String>,
+    pub content: String,
+    pub servings: Option<i32>,
+    pub prep_minutes: Option<i32>,
+    pub cook_minutes: Option<i32>,
+    pub total_minutes: Option<i32>,
+    pub difficulty: Option<String>,
+}
+
+impl From<&RecipeRevision> for RecipeSnapshot {
+    fn from(a: &RecipeRevision) -> Self {
+        RecipeSnapshot {
+            recipe_name: a.recipe_name.clone(),
+            primary_season: a.primary_season,
+            course_id: a.course_id,
+            book_id: a.book_id,
+            page: a.page,
+            recipe_url: a.recipe_url.clone(),
+            language: a.language.clone(),
+            ingredients: a.ingredients.lines().map(|x| x.to_string()).collect(),
+            tags: a.tags.lines().map(|x| x.to_string()).collect(),
+            content: a.content.clone(),
+            servings: a.servings,
+            prep_minutes: a.prep_minutes,
+            cook_minutes: a.cook_minutes,
+            total_minutes: a.total_minutes,
+            difficulty: a.difficulty.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EDiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+impl EDiffLine {
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            EDiffLine::Same(_) => " ",
+            EDiffLine::Added(_) => "+",
+            EDiffLine::Removed(_) => "-",
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        match self {
+            EDiffLine::Same(x) | EDiffLine::Added(x) | EDiffLine::Removed(x) => x.as_str(),
+        }
+    }
+}
+
+/// Line diff over the longest common subsequence, removals come before additions at the same spot.
+pub fn diff_lines(old: &str, new: &str) -> Vec<EDiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let mut res = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            res.push(EDiffLine::Same(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            res.push(EDiffLine::Removed(a[i].to_string()));
+            i += 1;
+        } else {
+            res.push(EDiffLine::Added(b[j].to_string()));
+            j += 1;
+        }
+    }
+    res.extend(a[i..].iter().map(|x| EDiffLine::Removed(x.to_string())));
+    res.extend(b[j..].iter().map(|x| EDiffLine::Added(x.to_string())));
+    res
+}
+
+/// Entries of `new` missing from `old` and the other way around.
+pub fn list_diff(old: &[String], new: &[String]) -> (Vec<String>, Vec<String>) {
+    let added = new.iter().filter(|x| !old.contains(x)).cloned().collect();
+    let removed = old.iter().filter(|x| !new.contains(x)).cloned().collect();
+    (added, removed)
+}
+
+/// The simple fields that differ, ids are shown by name.
+pub fn field_changes(old: &RecipeSnapshot, new: &RecipeSnapshot, course_names: &HashMap<i32, String>, book_names: &HashMap<i32, String>, season_names: &HashMap<i32, String>) -> Vec<FieldChange> {
+    let text = |x: &Option<String>| x.clone().unwrap_or_default();
+    let book = |x: Option<i32>| x.and_then(|y| book_names.get(&y).cloned()).unwrap_or_default();
+    let course = |x: i32| course_names.get(&x).cloned().unwrap_or_default();
+    let number = |x: Option<i32>| x.map(|y| y.to_string()).unwrap_or_default();
+    let season = |x: i32| season_names.get(&x).cloned().unwrap_or_default();
+    let difficulty = |x: &Option<String>| x.as_deref().and_then(EDifficulty::from_code).map(|y| y.to_string()).unwrap_or_default();
+    vec![
+        ("Name", text(&old.recipe_name), text(&new.recipe_name)),
+        ("Season", season(old.primary_season), season(new.primary_season)),
+        ("Course", course(old.course_id), course(new.course_id)),
+        ("Book", book(old.book_id), book(new.book_id)),
+        ("Page", number(old.page), number(new.page)),
+        ("URL", text(&old.recipe_url), text(&new.recipe_url)),
+        ("Language", text(&old.language), text(&new.language)),
+        ("Tags", old.tags.join(", "), new.tags.join(", ")),
+        ("Servings", number(old.servings), number(new.servings)),
+        ("Prep time", number(old.prep_minutes), number(new.prep_minutes)),
+        ("Cook time", number(old.cook_minutes), number(new.cook_minutes)),
+        ("Total time", number(old.total_minutes), number(new.total_minutes)),
+        ("Difficulty", difficulty(&old.difficulty), difficulty(&new.difficulty)),
+    ].into_iter()
+        .filter(|x| x.1 != x.2)
+        .map(|(field, old, new)| FieldChange { field, old, new })
+        .collect()
+}
+
+/// One row of the history on the detail page, compared with the revision before it.
+#[derive(Debug, Clone)]
+pub struct RevisionEntry {
+    pub revision_id: i32,
+    pub created_at: String,
+    pub author: Option<String>,
+    pub changes: Vec<FieldChange>,
+    pub added_ingredients: Vec<String>,
+    pub removed_ingredients: Vec<String>,
+    pub text_diff: Vec<EDiffLine>,
+    pub is_current: bool,
+}
+
+/// Newest first, revisions are expected oldest first.
+pub fn revision_entries(revisions: &[RecipeRevision], authors: &HashMap<i32, String>, course_names: &HashMap<i32, String>, book_names: &HashMap<i32, String>, season_names: &HashMap<i32, String>) -> Vec<RevisionEntry> {
+    let snapshots: Vec<RecipeSnapshot> = revisions.iter().map(RecipeSnapshot::from).collect();
+    revisions.iter()
+        .enumerate()
+        .map(|(i, revision)| {
+            let new = &snapshots[i];
+            let (changes, (added_ingredients, removed_ingredients), text_diff) = match i.checked_sub(1).map(|x| &snapshots[x]) {
+                Some(old) => (
+                    field_changes(old, new, course_names, book_names, season_names),
+                    list_diff(&old.ingredients, &new.ingredients),
+                    if old.content == new.content { vec![] } else { diff_lines(old.content.as_str(), new.content.as_str()) },
+                ),
+                None => (vec![], (vec![], vec![]), vec![]),
+            };
+            RevisionEntry {
+                revision_id: revision.revision_id.unwrap(),
+                created_at: revision.created_at.clone(),
+                author: revision.user_id.and_then(|x| authors.get(&x).cloned()),
+                changes,
+                added_ingredients,
+                removed_ingredients,
+                text_diff,
+                is_current: i + 1 == revisions.len(),
+            }
+        })
+        .rev()
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::revision::{diff_lines, field_changes, list_diff, EDiffLine, RecipeSnapshot};
+
+    #[test]
+    fn diff_keeps_common_lines() {
+        let res = diff_lines("Fry onions\nAdd eggs\nServe", "Fry onions\nAdd tomatoes\nAdd eggs\nServe hot");
+        assert_eq!(vec![
+            EDiffLine::Same("Fry onions".to_string()),
+            EDiffLine::Added("Add tomatoes".to_string()),
+            EDiffLine::Same("Add eggs".to_string()),
+            EDiffLine::Removed("Serve".to_string()),
+            EDiffLine::Added("Serve hot".to_string()),
+        ], res);
+    }
+
+    #[test]
+    fn diff_of_empty_text() {
+        assert_eq!(vec![EDiffLine::Added("a".to_string())], diff_lines("", "a"));
+        assert_eq!(vec![EDiffLine::Removed("a".to_string())], diff_lines("a", ""));
+        assert!(diff_lines("a\nb", "a\nb").iter().all(|x| x.prefix() == " "));
+    }
+
+    #[test]
+    fn only_changed_fields() {
+        let old = RecipeSnapshot { recipe_name: Some("Shakshuka".to_string()), primary_season: 1, course_id: 5, ingredients: vec!["egg".to_string()], ..Default::default() };
+        let new = RecipeSnapshot { course_id: 6, ingredients: vec!["egg".to_string(), "tomato".to_string()], ..old.clone() };
+        let course_names = HashMap::from([(5, "Mains".to_string()), (6, "Sides".to_string())]);
+        let res = field_changes(&old, &new, &course_names, &HashMap::new(), &HashMap::new());
+        assert_eq!(1, res.len());
+        assert_eq!(("Course", "Mains", "Sides"), (res[0].field, res[0].old.as_str(), res[0].new.as_str()));
+        assert_eq!((vec!["tomato".to_string()], vec![]), list_diff(&old.ingredients, &new.ingredients));
+    }
+}
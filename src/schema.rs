@@ -5,6 +5,47 @@ diesel::table! {
         book_id -> Nullable<Integer>,
         book_name -> Nullable<Text>,
         created_at -> Nullable<Float>,
+        language -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    collection (collection_id) {
+        collection_id -> Nullable<Integer>,
+        user_id -> Integer,
+        collection_name -> Text,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    collection_recipe (collection_id, recipe_id) {
+        collection_id -> Integer,
+        recipe_id -> Integer,
+        position -> Integer,
+        note -> Nullable<Text>,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    collection_share (collection_id, user_id) {
+        collection_id -> Integer,
+        user_id -> Integer,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    cooked (cooked_id) {
+        cooked_id -> Nullable<Integer>,
+        user_id -> Integer,
+        recipe_id -> Integer,
+        cooked_on -> Text,
+        created_at -> Nullable<Float>,
+        notes -> Nullable<Text>,
+        servings -> Nullable<Integer>,
+        photo -> Nullable<Text>,
     }
 }
 
@@ -16,11 +57,74 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    equipment (equipment_id) {
+        equipment_id -> Nullable<Integer>,
+        name -> Text,
+        created_at -> Nullable<Float>,
+    }
+}
+
 diesel::table! {
     ingredient (id) {
         id -> Nullable<Integer>,
         name -> Nullable<Text>,
         created_at -> Nullable<Float>,
+        classified -> Bool,
+    }
+}
+
+diesel::table! {
+    ingredient_attribute (ingredient_id, attribute) {
+        ingredient_id -> Integer,
+        attribute -> Text,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    ingredient_nutrient (ingredient_id) {
+        ingredient_id -> Integer,
+        nutrient_id -> Integer,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    ingredient_price (price_id) {
+        price_id -> Nullable<Integer>,
+        ingredient_id -> Integer,
+        price -> Float,
+        quantity -> Float,
+        unit -> Text,
+        piece_grams -> Nullable<Float>,
+        currency -> Text,
+        priced_on -> Text,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    nutrient (nutrient_id) {
+        nutrient_id -> Nullable<Integer>,
+        source -> Text,
+        source_code -> Text,
+        food_name -> Text,
+        energy_kcal -> Nullable<Float>,
+        protein_g -> Nullable<Float>,
+        fat_g -> Nullable<Float>,
+        carbs_g -> Nullable<Float>,
+        fibre_g -> Nullable<Float>,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    produce_month (ingredient_id, region, month) {
+        ingredient_id -> Integer,
+        region -> Text,
+        month -> Integer,
+        created_at -> Nullable<Float>,
     }
 }
 
@@ -34,6 +138,13 @@ diesel::table! {
         recipe_url -> Nullable<Text>,
         created_at -> Nullable<Float>,
         page -> Nullable<Integer>,
+        language -> Nullable<Text>,
+        servings -> Nullable<Integer>,
+        prep_minutes -> Nullable<Integer>,
+        cook_minutes -> Nullable<Integer>,
+        total_minutes -> Nullable<Integer>,
+        difficulty -> Nullable<Text>,
+        modified_at -> Nullable<Text>,
     }
 }
 
@@ -44,6 +155,36 @@ diesel::table! {
         recipe_id -> Integer,
         content -> Text,
         created_at -> Float,
+        parent_id -> Nullable<Integer>,
+        modified_at -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    recipe_diet_override (recipe_id, diet) {
+        recipe_id -> Integer,
+        diet -> Text,
+        is_diet -> Bool,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    recipe_equipment (recipe_id, equipment_id) {
+        recipe_id -> Integer,
+        equipment_id -> Integer,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    recipe_image (image_id) {
+        image_id -> Nullable<Integer>,
+        recipe_id -> Integer,
+        file_name -> Text,
+        is_cover -> Bool,
+        position -> Integer,
+        created_at -> Nullable<Float>,
     }
 }
 
@@ -52,6 +193,68 @@ diesel::table! {
         recipe_id -> Integer,
         ingredient_id -> Integer,
         created_at -> Nullable<Float>,
+        amount_grams -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    recipe_link (recipe_id, linked_recipe_id, link_type) {
+        recipe_id -> Integer,
+        linked_recipe_id -> Integer,
+        link_type -> Text,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    recipe_rating (user_id, recipe_id) {
+        user_id -> Integer,
+        recipe_id -> Integer,
+        rating -> Nullable<Integer>,
+        cook_again -> Bool,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    recipe_revision (revision_id) {
+        revision_id -> Nullable<Integer>,
+        recipe_id -> Integer,
+        user_id -> Nullable<Integer>,
+        recipe_name -> Nullable<Text>,
+        primary_season -> Integer,
+        course_id -> Integer,
+        book_id -> Nullable<Integer>,
+        page -> Nullable<Integer>,
+        recipe_url -> Nullable<Text>,
+        language -> Nullable<Text>,
+        ingredients -> Text,
+        tags -> Text,
+        content -> Text,
+        created_at -> Text,
+        servings -> Nullable<Integer>,
+        prep_minutes -> Nullable<Integer>,
+        cook_minutes -> Nullable<Integer>,
+        total_minutes -> Nullable<Integer>,
+        difficulty -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    recipe_season (recipe_id, season_id) {
+        recipe_id -> Integer,
+        season_id -> Integer,
+        start_month -> Nullable<Integer>,
+        end_month -> Nullable<Integer>,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    recipe_tag (recipe_id, tag_id) {
+        recipe_id -> Integer,
+        tag_id -> Integer,
+        created_at -> Nullable<Float>,
     }
 }
 
@@ -64,11 +267,43 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    saved_search (saved_search_id) {
+        saved_search_id -> Nullable<Integer>,
+        user_id -> Integer,
+        search_name -> Text,
+        query -> Text,
+        smart_collection -> Bool,
+        created_at -> Nullable<Float>,
+    }
+}
+
 diesel::table! {
     season (season_id) {
         season_id -> Nullable<Integer>,
         tag_name -> Nullable<Text>,
         created_at -> Nullable<Float>,
+        start_month -> Nullable<Integer>,
+        end_month -> Nullable<Integer>,
+        position -> Integer,
+        all_year -> Bool,
+    }
+}
+
+diesel::table! {
+    synonym (synonym_id) {
+        synonym_id -> Nullable<Integer>,
+        group_id -> Integer,
+        term -> Text,
+        created_at -> Nullable<Float>,
+    }
+}
+
+diesel::table! {
+    tag (tag_id) {
+        tag_id -> Nullable<Integer>,
+        tag_name -> Text,
+        created_at -> Nullable<Float>,
     }
 }
 
@@ -86,25 +321,84 @@ diesel::table! {
         email -> Text,
         pw_hash -> Text,
         created_at -> Nullable<Float>,
+        is_admin -> Bool,
+        display_name -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    user_equipment (user_id, equipment_id) {
+        user_id -> Integer,
+        equipment_id -> Integer,
+        created_at -> Nullable<Float>,
     }
 }
 
+diesel::joinable!(collection -> user (user_id));
+diesel::joinable!(collection_recipe -> collection (collection_id));
+diesel::joinable!(collection_recipe -> recipe (recipe_id));
+diesel::joinable!(collection_share -> collection (collection_id));
+diesel::joinable!(collection_share -> user (user_id));
+diesel::joinable!(cooked -> recipe (recipe_id));
+diesel::joinable!(cooked -> user (user_id));
+diesel::joinable!(ingredient_attribute -> ingredient (ingredient_id));
+diesel::joinable!(ingredient_nutrient -> ingredient (ingredient_id));
+diesel::joinable!(ingredient_nutrient -> nutrient (nutrient_id));
+diesel::joinable!(ingredient_price -> ingredient (ingredient_id));
+diesel::joinable!(produce_month -> ingredient (ingredient_id));
 diesel::joinable!(recipe -> book (book_id));
 diesel::joinable!(recipe -> course (course_id));
 diesel::joinable!(recipe -> season (primary_season));
 diesel::joinable!(recipe_comment -> user (user_id));
+diesel::joinable!(recipe_diet_override -> recipe (recipe_id));
+diesel::joinable!(recipe_equipment -> equipment (equipment_id));
+diesel::joinable!(recipe_equipment -> recipe (recipe_id));
+diesel::joinable!(recipe_image -> recipe (recipe_id));
 diesel::joinable!(recipe_ingredient -> ingredient (recipe_id));
+diesel::joinable!(recipe_rating -> recipe (recipe_id));
+diesel::joinable!(recipe_rating -> user (user_id));
+diesel::joinable!(recipe_revision -> recipe (recipe_id));
+diesel::joinable!(recipe_revision -> user (user_id));
+diesel::joinable!(recipe_season -> recipe (recipe_id));
+diesel::joinable!(recipe_season -> season (season_id));
+diesel::joinable!(recipe_tag -> recipe (recipe_id));
+diesel::joinable!(recipe_tag -> tag (tag_id));
+diesel::joinable!(saved_search -> user (user_id));
 diesel::joinable!(tried -> user (user_id));
+diesel::joinable!(user_equipment -> equipment (equipment_id));
+diesel::joinable!(user_equipment -> user (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     book,
+    collection,
+    collection_recipe,
+    collection_share,
+    cooked,
     course,
+    equipment,
     ingredient,
+    ingredient_attribute,
+    ingredient_nutrient,
+    ingredient_price,
+    nutrient,
+    produce_month,
     recipe,
     recipe_comment,
+    recipe_diet_override,
+    recipe_equipment,
+    recipe_image,
     recipe_ingredient,
+    recipe_link,
+    recipe_rating,
+    recipe_revision,
+    recipe_season,
+    recipe_tag,
     recipe_text,
+    saved_search,
     season,
+    synonym,
+    tag,
     tried,
     user,
+    user_equipment,
 );
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use tantivy::collector::{FacetCollector, FacetCounts};
+use tantivy::schema::Facet;
+
+use crate::diet::EDiet;
+use crate::text_search::{SCHEMA_BOOK, SCHEMA_COLLECTION, SCHEMA_COURSE, SCHEMA_DIET, SCHEMA_SEASON};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FacetValue {
+    pub id: i32,
+    pub name: String,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SearchFacets {
+    pub books: Vec<FacetValue>,
+    pub courses: Vec<FacetValue>,
+    pub seasons: Vec<FacetValue>,
+    pub collections: Vec<FacetValue>,
+    pub diets: Vec<FacetValue>,
+}
+
+impl SearchFacets {
+    pub fn is_empty(&self) -> bool {
+        self.books.is_empty() && self.courses.is_empty() && self.seasons.is_empty() && self.collections.is_empty() && self.diets.is_empty()
+    }
+}
+
+/// Names of the facet values by id.
+pub struct FacetNames {
+    pub books: HashMap<i32, String>,
+    pub courses: HashMap<i32, String>,
+    pub seasons: HashMap<i32, String>,
+    pub collections: HashMap<i32, String>,
+}
+
+pub fn facet_collector(field_name: &str) -> FacetCollector {
+    let mut collector = FacetCollector::for_field(field_name);
+    collector.add_facet(facet_root(field_name).as_str());
+    collector
+}
+
+pub fn build_search_facets(book_counts: &FacetCounts, course_counts: &FacetCounts, season_counts: &FacetCounts, collection_counts: &FacetCounts, diet_counts: &FacetCounts,
+                           names: &FacetNames) -> SearchFacets {
+    let diet_codes: HashMap<i32, String> = EDiet::get_diets().iter()
+        .map(|x| (x.value_i32(), x.code().to_string()))
+        .collect();
+    let diets: Vec<FacetValue> = to_facet_values(diet_counts, SCHEMA_DIET, &diet_codes).into_iter()
+        .map(|x| FacetValue { name: EDiet::to_map()[&x.id].to_string(), ..x })
+        .collect();
+    SearchFacets {
+        books: to_facet_values(book_counts, SCHEMA_BOOK, &names.books),
+        courses: to_facet_values(course_counts, SCHEMA_COURSE, &names.courses),
+        seasons: to_id_facet_values(season_counts, SCHEMA_SEASON, &names.seasons),
+        collections: to_id_facet_values(collection_counts, SCHEMA_COLLECTION, &names.collections),
+        diets,
+    }
+}
+
+fn to_facet_values(counts: &FacetCounts, field_name: &str, id_to_name: &HashMap<i32, String>) -> Vec<FacetValue> {
+    let name_to_id: HashMap<&str, i32> = id_to_name.iter()
+        .map(|(id, name)| (name.as_str(), *id))
+        .collect();
+    let mut values: Vec<FacetValue> = counts.get(facet_root(field_name).as_str())
+        .filter_map(|(facet, count)| {
+            let name = facet_leaf(facet)?;
+            let id = *name_to_id.get(name.as_str())?;
+            Some(FacetValue { id, name, count })
+        })
+        .collect();
+    values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    values
+}
+
+/// For facets indexed by id, anything missing from `id_to_name` is left out, which hides other users' collections and deleted seasons.
+fn to_id_facet_values(counts: &FacetCounts, field_name: &str, id_to_name: &HashMap<i32, String>) -> Vec<FacetValue> {
+    let mut values: Vec<FacetValue> = counts.get(facet_root(field_name).as_str())
+        .filter_map(|(facet, count)| {
+            let id = facet_leaf(facet)?.parse::<i32>().ok()?;
+            let name = id_to_name.get(&id)?.clone();
+            Some(FacetValue { id, name, count })
+        })
+        .collect();
+    values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    values
+}
+
+fn facet_root(field_name: &str) -> String {
+    format!("/{}", field_name)
+}
+
+fn facet_leaf(facet: &Facet) -> Option<String> {
+    facet.to_path().last().filter(|x| !x.is_empty()).map(|x| x.to_string())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tantivy::schema::Facet;
+
+    use crate::search::facets::facet_leaf;
+
+    #[test]
+    fn leaf_of_book_facet() {
+        let facet = Facet::from("/book/Ottolenghi Simple");
+        assert_eq!(Some("Ottolenghi Simple".to_string()), facet_leaf(&facet));
+    }
+
+    #[test]
+    fn leaf_of_root() {
+        assert_eq!(None, facet_leaf(&Facet::root()));
+    }
+}
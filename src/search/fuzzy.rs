@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use tantivy::query::{BooleanQuery, FuzzyTermQuery, Occur, Query};
+use tantivy::schema::Field;
+use tantivy::tokenizer::TokenStream;
+use tantivy::{Index, Searcher, Term};
+
+use crate::search::language::ERecipeLanguage;
+use crate::text_search::{SCHEMA_INGREDIENTS, SCHEMA_TITLE, SCHEMA_WORDS, WORDS_TOKENIZER};
+
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+pub fn analyze(index: &Index, tokenizer_name: &str, text: &str) -> Vec<String> {
+    let mut analyzer = index.tokenizers().get(tokenizer_name).expect("The search tokenizer should be registered");
+    let mut stream = analyzer.token_stream(text);
+    let mut tokens: Vec<String> = vec![];
+    while stream.advance() {
+        tokens.push(stream.token().text.clone());
+    }
+    tokens
+}
+
+/// Titles in every language and the ingredients, each with the tokenizer it was indexed with.
+fn fuzzy_fields(index: &Index) -> Vec<(Field, String)> {
+    let schema = index.schema();
+    let mut fields: Vec<(Field, String)> = ERecipeLanguage::get_languages().iter()
+        .map(|x| (schema.get_field(x.field_name(SCHEMA_TITLE).as_str()).unwrap(), x.tokenizer_name()))
+        .collect();
+    fields.push((schema.get_field(SCHEMA_INGREDIENTS).unwrap(), ERecipeLanguage::English.tokenizer_name()));
+    fields
+}
+
+/// Short words only get a single edit, otherwise "egg" would match half the index.
+fn allowed_distance(token: &str) -> u8 {
+    if token.chars().count() <= 4 { 1 } else { 2 }
+}
+
+/// Every word of the search text has to match title or ingredients, either within a small edit distance or as a prefix.
+pub fn build_fuzzy_query(index: &Index, text: &str, filter: Option<Box<dyn Query>>) -> Box<dyn Query> {
+    let fields = fuzzy_fields(index);
+    let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![];
+    for word in text.split_whitespace() {
+        let alternatives: Vec<(Occur, Box<dyn Query>)> = fields.iter()
+            .flat_map(|(field, tokenizer_name)| analyze(index, tokenizer_name, word).into_iter().map(|token| (*field, token)))
+            .flat_map(|(field, token)| {
+                let term = Term::from_field_text(field, &token);
+                let fuzzy: Box<dyn Query> = Box::new(FuzzyTermQuery::new(term.clone(), allowed_distance(&token), true));
+                let prefix: Box<dyn Query> = Box::new(FuzzyTermQuery::new_prefix(term, 0, true));
+                vec![(Occur::Should, fuzzy), (Occur::Should, prefix)]
+            })
+            .collect();
+        // stop words vanish in every language
+        if alternatives.is_empty() {
+            continue;
+        }
+        clauses.push((Occur::Must, Box::new(BooleanQuery::new(alternatives))));
+    }
+    if let Some(filter_query) = filter {
+        clauses.push((Occur::Must, filter_query));
+    }
+    Box::new(BooleanQuery::new(clauses))
+}
+
+/// Replaces every word of the search text with the closest word of a title or ingredient. The words are
+/// compared unstemmed, so the suggestion is something that can be typed again.
+pub fn suggest(index: &Index, searcher: &Searcher, text: &str) -> Option<String> {
+    let tokens = analyze(index, WORDS_TOKENIZER, text);
+    if tokens.is_empty() {
+        return None;
+    }
+    let vocabulary = collect_vocabulary(searcher, &[index.schema().get_field(SCHEMA_WORDS).unwrap()]);
+    let suggestion = tokens.iter()
+        .map(|token| closest_term(token, &vocabulary).unwrap_or(token.clone()))
+        .join(" ");
+    Some(suggestion).filter(|x| *x != tokens.join(" "))
+}
+
+fn collect_vocabulary(searcher: &Searcher, fields: &[Field]) -> Vec<String> {
+    let mut vocabulary: Vec<String> = vec![];
+    for segment_reader in searcher.segment_readers() {
+        for field in fields {
+            let Ok(inverted_index) = segment_reader.inverted_index(*field) else { continue };
+            let Ok(mut terms) = inverted_index.terms().stream() else { continue };
+            while terms.advance() {
+                if let Ok(term) = std::str::from_utf8(terms.key()) {
+                    vocabulary.push(term.to_string());
+                }
+            }
+        }
+    }
+    vocabulary.into_iter().unique().collect()
+}
+
+fn closest_term(token: &str, vocabulary: &[String]) -> Option<String> {
+    let mut distances: HashMap<&String, usize> = HashMap::new();
+    for term in vocabulary {
+        distances.insert(term, levenshtein(token, term));
+    }
+    distances.into_iter()
+        .filter(|(_, distance)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)))
+        .map(|(term, _)| term.clone())
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b_chars.len()]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::search::fuzzy::{closest_term, levenshtein};
+
+    #[test]
+    fn levenshtein_distances() {
+        assert_eq!(0, levenshtein("shakshuka", "shakshuka"));
+        assert_eq!(1, levenshtein("shakshuka", "shakshouka"));
+        assert_eq!(3, levenshtein("", "egg"));
+    }
+
+    #[test]
+    fn closest_term_within_distance() {
+        let vocabulary = vec!["aubergine".to_string(), "courgette".to_string(), "egg".to_string()];
+        assert_eq!(Some("aubergine".to_string()), closest_term("aubergne", &vocabulary));
+        assert_eq!(None, closest_term("pomegranate", &vocabulary));
+    }
+}
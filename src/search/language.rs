@@ -0,0 +1,97 @@
+use std::fmt;
+
+use tantivy::tokenizer::Language;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum ERecipeLanguage {
+    English,
+    German,
+    French,
+    Italian,
+}
+
+impl ERecipeLanguage {
+    pub fn get_languages() -> Vec<ERecipeLanguage> {
+        vec![ERecipeLanguage::English, ERecipeLanguage::German, ERecipeLanguage::French, ERecipeLanguage::Italian]
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ERecipeLanguage::English => "en",
+            ERecipeLanguage::German => "de",
+            ERecipeLanguage::French => "fr",
+            ERecipeLanguage::Italian => "it",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<ERecipeLanguage> {
+        ERecipeLanguage::get_languages().into_iter()
+            .find(|x| x.code() == code.trim().to_lowercase())
+    }
+
+    /// Recipes without a language of their own inherit the one of their book, English otherwise.
+    pub fn resolve(recipe_language: Option<&String>, book_language: Option<&String>) -> ERecipeLanguage {
+        recipe_language.and_then(|x| ERecipeLanguage::from_code(x))
+            .or_else(|| book_language.and_then(|x| ERecipeLanguage::from_code(x)))
+            .unwrap_or(ERecipeLanguage::English)
+    }
+
+    pub fn stemmer_language(&self) -> Language {
+        match self {
+            ERecipeLanguage::English => Language::English,
+            ERecipeLanguage::German => Language::German,
+            ERecipeLanguage::French => Language::French,
+            ERecipeLanguage::Italian => Language::Italian,
+        }
+    }
+
+    /// English keeps the analyzer and field names from before languages existed.
+    pub fn tokenizer_name(&self) -> String {
+        match self {
+            ERecipeLanguage::English => "ascii".to_string(),
+            _ => format!("ascii_{}", self.code()),
+        }
+    }
+
+    pub fn field_name(&self, base: &str) -> String {
+        match self {
+            ERecipeLanguage::English => base.to_string(),
+            _ => format!("{}_{}", base, self.code()),
+        }
+    }
+}
+
+impl fmt::Display for ERecipeLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ERecipeLanguage::English => write!(f, "English"),
+            ERecipeLanguage::German => write!(f, "German"),
+            ERecipeLanguage::French => write!(f, "French"),
+            ERecipeLanguage::Italian => write!(f, "Italian"),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::search::language::ERecipeLanguage;
+
+    #[test]
+    fn recipe_language_wins() {
+        let res = ERecipeLanguage::resolve(Some(&"fr".to_string()), Some(&"de".to_string()));
+        assert_eq!(ERecipeLanguage::French, res);
+    }
+
+    #[test]
+    fn falls_back_to_book_then_english() {
+        assert_eq!(ERecipeLanguage::German, ERecipeLanguage::resolve(None, Some(&"de".to_string())));
+        assert_eq!(ERecipeLanguage::English, ERecipeLanguage::resolve(Some(&"xx".to_string()), None));
+    }
+
+    #[test]
+    fn field_names() {
+        assert_eq!("title", ERecipeLanguage::English.field_name("title"));
+        assert_eq!("body_it", ERecipeLanguage::Italian.field_name("body"));
+    }
+}
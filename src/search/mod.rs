@@ -1,2 +1,5 @@
+pub mod facets;
+pub mod fuzzy;
+pub mod language;
 pub mod search_toggle;
 pub mod synonym_tokenizer;
\ No newline at end of file
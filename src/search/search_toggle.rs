@@ -1,31 +1,136 @@
 use std::collections::HashMap;
 
 use crate::args::SearchPrefill;
+use crate::cost::CostEstimate;
+use crate::diet::EDiet;
 use crate::models::{FullRecipe, QBook, QCourse};
-use crate::parsetypes::ESeason;
-use crate::queries::{build_index_search_query, build_search_query};
-use crate::schema::book::dsl::book;
-use crate::schema::course::dsl::course;
-use crate::text_search::{build_query, SCHEMA_BODY, SCHEMA_INGREDIENTS, SCHEMA_RECIPE_ID, SCHEMA_TITLE};
-use diesel::{sql_query, RunQueryDsl, SqliteConnection};
+use crate::queries::{build_index_search_query, build_search_query, query_accessible_collections, query_rating_summaries, query_recipe_costs, query_recipes_to_diets, query_recipes_to_equipment_ids, query_recipes_to_tags, query_seasons, query_user_equipment_ids};
+use crate::rating::rating_order;
+use crate::recipe_time::{effective_minutes, EDifficulty};
+use crate::search::facets::{build_search_facets, facet_collector, FacetNames, SearchFacets};
+use crate::search::fuzzy::{build_fuzzy_query, suggest};
+use crate::text_search::{build_filter_query, build_query, text_search_fields, SCHEMA_BOOK, SCHEMA_COLLECTION, SCHEMA_COURSE, SCHEMA_DIET, SCHEMA_RECIPE_ID, SCHEMA_SEASON};
+use chrono::Local;
+use diesel::{sql_query, ExpressionMethods, QueryDsl, RunQueryDsl, SqliteConnection};
 use tantivy::collector::TopDocs;
-use tantivy::query::QueryParser;
+use tantivy::query::{Query, QueryParser, TermSetQuery};
 use tantivy::schema::Value;
-use tantivy::{Index, TantivyDocument};
+use tantivy::{Index, TantivyDocument, Term};
 
-pub fn search(search_args: &SearchPrefill, con: &mut SqliteConnection, index: &Index, user_id: i32) -> Vec<FullRecipe> {
-    let sql_string: String = if search_args.legacy.filter(|x| x.clone() == 1).is_some() { build_search_query(&search_args, user_id) } else { build_tantivy_search_for_sql(search_args, con, index, user_id) };
+pub struct SearchOutcome {
+    pub recipes: Vec<FullRecipe>,
+    pub facets: Option<SearchFacets>,
+    pub suggestion: Option<String>,
+}
+
+pub fn search(search_args: &SearchPrefill, con: &mut SqliteConnection, index: &Index, user_id: i32) -> SearchOutcome {
+    let legacy = search_args.legacy.filter(|x| *x == 1).is_some();
+    let sql_string = if legacy { build_search_query(search_args, user_id) } else { build_tantivy_search_for_sql(search_args, con, index, user_id) };
 
 
-    let recipes = sql_query(sql_string)
+    let mut recipes = sql_query(sql_string)
         .load::<FullRecipe>(con)
         .ok().unwrap_or(vec![]);
-    return recipes;
+    if legacy {
+        apply_legacy_filters(search_args, con, user_id, &mut recipes);
+    }
+    if search_args.equipped.is_some() {
+        let owned = query_user_equipment_ids(con, user_id);
+        let needed = query_recipes_to_equipment_ids(con);
+        recipes.retain(|x| needed.get(&x.recipe_id.unwrap()).is_none_or(|y| y.iter().all(|z| owned.contains(z))));
+    }
+    let costs = if search_args.max_cost.is_some() || search_args.sort.as_deref() == Some("cost") { query_recipe_costs(con, Local::now().date_naive()) } else { HashMap::new() };
+    let cost_per_serving = |x: &FullRecipe| costs.get(&x.recipe_id.unwrap()).and_then(CostEstimate::comparable_per_serving);
+    if let Some(max_cost) = search_args.max_cost {
+        recipes.retain(|x| cost_per_serving(x).is_some_and(|y| y <= max_cost));
+    }
+    // counted on what is left after the filters above so the numbers match the result list
+    let facets = if legacy { None } else { Some(count_facets(con, index, user_id, &recipes)) };
+    match search_args.sort.as_deref() {
+        Some("rating") => {
+            let ratings = query_rating_summaries(con);
+            recipes.sort_by(|a, b| rating_order(ratings.get(&a.recipe_id.unwrap()), ratings.get(&b.recipe_id.unwrap())));
+        }
+        Some("name") => recipes.sort_by_key(|x| x.recipe_name.clone().unwrap_or_default().to_lowercase()),
+        // unknown values go last
+        Some("time") => recipes.sort_by_key(|x| effective_minutes(x.prep_minutes, x.cook_minutes, x.total_minutes).unwrap_or(i32::MAX)),
+        Some("difficulty") => recipes.sort_by_key(|x| x.difficulty.as_deref().and_then(EDifficulty::from_code).map(|y| y.rank()).unwrap_or(i64::MAX)),
+        Some("cost") => recipes.sort_by(|a, b| cost_per_serving(a).unwrap_or(f32::MAX).total_cmp(&cost_per_serving(b).unwrap_or(f32::MAX))),
+        _ => {}
+    }
+    // the suggestions come from the index, which the legacy search does not use
+    let suggestion = if recipes.is_empty() && !legacy {
+        suggest(index, &index.reader().unwrap().searcher(), search_args.template_name().as_str())
+    } else {
+        None
+    };
+    SearchOutcome { recipes, facets, suggestion }
+}
+
+/// Filters the index applies itself, the legacy SQL search leaves them to this.
+fn apply_legacy_filters(search_args: &SearchPrefill, con: &mut SqliteConnection, user_id: i32, recipes: &mut Vec<FullRecipe>) {
+    let accessible = query_accessible_collections(con, user_id);
+    if let Some(cur_collection_id) = search_args.collection.filter(|x| accessible.iter().any(|y| y.collection_id == Some(*x))) {
+        use crate::schema::collection_recipe::dsl::*;
+        let members: Vec<i32> = collection_recipe.filter(collection_id.eq(cur_collection_id)).select(recipe_id).load::<i32>(con).unwrap();
+        recipes.retain(|x| x.recipe_id.is_some_and(|y| members.contains(&y)));
+    }
+    if let Some(cur_tag) = search_args.tag.as_ref().map(|x| x.trim().to_lowercase()).filter(|x| !x.is_empty()) {
+        let recipes_to_tags = query_recipes_to_tags(con);
+        recipes.retain(|x| recipes_to_tags.get(&x.recipe_id.unwrap()).is_some_and(|y| y.iter().any(|z| z.to_lowercase() == cur_tag)));
+    }
+    let diets: Vec<EDiet> = EDiet::get_diets().into_iter().filter(|x| search_args.has_diet(*x)).collect();
+    if !diets.is_empty() {
+        let recipes_to_diets = query_recipes_to_diets(con);
+        recipes.retain(|x| recipes_to_diets.get(&x.recipe_id.unwrap()).is_some_and(|y| diets.iter().all(|z| y.contains(z))));
+    }
+    // like the index ranges, unknown times and difficulties never match
+    if search_args.min_minutes.is_some() || search_args.max_minutes.is_some() {
+        let (min_minutes, max_minutes) = (search_args.min_minutes.unwrap_or(0), search_args.max_minutes.unwrap_or(i32::MAX));
+        recipes.retain(|x| effective_minutes(x.prep_minutes, x.cook_minutes, x.total_minutes).is_some_and(|y| y >= min_minutes && y <= max_minutes));
+    }
+    if let Some(max_difficulty) = search_args.max_difficulty() {
+        recipes.retain(|x| x.difficulty.as_deref().and_then(EDifficulty::from_code).is_some_and(|y| y.rank() <= max_difficulty.rank()));
+    }
+}
+
+/// Facet counts over the recipes of the result list.
+fn count_facets(con: &mut SqliteConnection, index: &Index, user_id: i32, recipes: &[FullRecipe]) -> SearchFacets {
+    let recipe_id_field = index.schema().get_field(SCHEMA_RECIPE_ID).unwrap();
+    let query = TermSetQuery::new(recipes.iter().map(|x| Term::from_field_i64(recipe_id_field, x.recipe_id.unwrap() as i64)));
+    let names = FacetNames {
+        books: {
+            use crate::schema::book::dsl::*;
+            book.load::<QBook>(con).unwrap().into_iter()
+                .map(|x| (x.book_id.unwrap(), x.book_name.unwrap()))
+                .collect()
+        },
+        courses: {
+            use crate::schema::course::dsl::*;
+            course.load::<QCourse>(con).unwrap().into_iter()
+                .map(|x| (x.course_id.unwrap(), x.course_name.unwrap()))
+                .collect()
+        },
+        seasons: query_seasons(con).into_iter().map(|x| (x.season_id, x.name)).collect(),
+        collections: query_accessible_collections(con, user_id).into_iter()
+            .map(|x| (x.collection_id.unwrap(), x.collection_name))
+            .collect(),
+    };
+    // collectors only combine up to four per tuple
+    let collectors = ((facet_collector(SCHEMA_BOOK), facet_collector(SCHEMA_COURSE)), (facet_collector(SCHEMA_SEASON), facet_collector(SCHEMA_COLLECTION)), facet_collector(SCHEMA_DIET));
+    let ((book_counts, course_counts), (season_counts, collection_counts), diet_counts) = index.reader().unwrap().searcher().search(&query, &collectors).unwrap();
+    build_search_facets(&book_counts, &course_counts, &season_counts, &collection_counts, &diet_counts, &names)
 }
 
 fn build_tantivy_search_for_sql(search_args: &SearchPrefill, con: &mut SqliteConnection, index: &Index, user_id: i32) -> String {
     let reader = index.reader().unwrap();
-    let query_parser = QueryParser::for_index(&index, vec![index.schema().get_field(SCHEMA_TITLE).unwrap(), index.schema().get_field(SCHEMA_INGREDIENTS).unwrap(), index.schema().get_field(SCHEMA_BODY).unwrap()]);
+    let collection_ids: Vec<i32> = query_accessible_collections(con, user_id).into_iter()
+        .map(|x| x.collection_id.unwrap())
+        .collect();
+    let mut search_args = search_args.clone();
+    search_args.collection = search_args.collection.filter(|x| collection_ids.contains(x));
+    let search_args = &search_args;
+    let query_parser = QueryParser::for_index(index, text_search_fields(&index.schema()));
 
     use crate::schema::book::dsl::*;
     let books: HashMap<i32, String> = book.load::<QBook>(con).unwrap()
@@ -34,7 +139,7 @@ fn build_tantivy_search_for_sql(search_args: &SearchPrefill, con: &mut SqliteCon
         .collect();
 
 
-    let seasons: HashMap<usize, ESeason> = ESeason::to_map();
+    let seasons = query_seasons(con);
     use crate::schema::course::dsl::*;
 
     let course_names: HashMap<i32, String> = course.load::<QCourse>(con).unwrap()
@@ -43,12 +148,17 @@ fn build_tantivy_search_for_sql(search_args: &SearchPrefill, con: &mut SqliteCon
         .collect();
 
 
-    let query_string = build_query(search_args.clone(), books, seasons, course_names);
-    let parse_res = query_parser.parse_query(query_string.as_str());
-    let query = query_parser.parse_query(query_string.as_str()).unwrap();
+    let query: Box<dyn Query> = if search_args.fuzzy.filter(|x| *x == 1).is_some() {
+        let filter_string = build_filter_query(search_args.clone(), books.clone(), &seasons, course_names.clone());
+        let filter = Some(filter_string).filter(|x| !x.trim().is_empty()).map(|x| query_parser.parse_query(x.as_str()).unwrap());
+        build_fuzzy_query(index, search_args.template_name().as_str(), filter)
+    } else {
+        let query_string = build_query(search_args.clone(), books.clone(), &seasons, course_names.clone());
+        query_parser.parse_query(query_string.as_str()).unwrap()
+    };
     let searcher = reader.searcher();
-    let results = searcher.search(&query, &TopDocs::with_limit(1024));
-    let index_recipes: Vec<TantivyDocument> = results.unwrap().iter().map(|x| searcher.doc(x.1))
+    let top_docs = searcher.search(&query, &TopDocs::with_limit(1024)).unwrap();
+    let index_recipes: Vec<TantivyDocument> = top_docs.iter().map(|x| searcher.doc(x.1))
         .filter(|x| x.is_ok())
         .map(|x| x.unwrap())
         .collect();
@@ -57,7 +167,7 @@ fn build_tantivy_search_for_sql(search_args: &SearchPrefill, con: &mut SqliteCon
         e"))
         .map(|x| x.as_i64().unwrap())
         .collect();
-    return build_index_search_query(recipe_ids, search_args, user_id);
+    build_index_search_query(recipe_ids, search_args, user_id)
 }
 
 
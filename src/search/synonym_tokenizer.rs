@@ -1,38 +1,86 @@
 use std::collections::HashMap;
-use std::mem;
-
-use tantivy::tokenizer::{Token, TokenFilter, Tokenizer, TokenStream};
-
-fn get_default_args() -> HashMap<String, String> {
-    let res: HashMap<String, String> = HashMap::from(
-        [
-            ("joghurt".to_string(), "yoghurt".to_string()),
-            ("yogurt".to_string(), "yoghurt".to_string()),
-            ("zucchini".to_string(), "courgette".to_string()),
-            ("filo".to_string(), "phyllo".to_string()),
-            ("swede".to_string(), "rutabagaba".to_string()),
-            ("chilli".to_string(), "chili".to_string()),
-            ("steinpilz".to_string(), "porcini".to_string()),
-            ("cep".to_string(), "porcini".to_string()),
-            ("houmus".to_string(), "hummus".to_string()),
-            ("eggplant".to_string(), "aubergine".to_string()),
-            ("za'atar".to_string(), "zaatar".to_string()),
-            ("zatar".to_string(), "zaatar".to_string()),
-            ("soya".to_string(), "soy".to_string()),
-            ("cornflour".to_string(), "cornstarch".to_string()),
-            ("ladyfingers".to_string(), "savoiardi".to_string()),
-            ("verjus".to_string(), "verjuice".to_string()),
-            ("pumpkin".to_string(), "squash".to_string()),
-            ("kaffir".to_string(), "makrut".to_string()),
-            ("gherkin".to_string(), "cornichon".to_string()),
-        ]
-    );
-    return res;
+use std::sync::{Arc, RwLock};
+
+use itertools::Itertools;
+use tantivy::tokenizer::{TextAnalyzer, Token, TokenFilter, Tokenizer, TokenStream};
+
+/// Synonym groups normalised with the analyzer that runs before the [`SynonymFilter`], so stemmed
+/// and ascii-folded tokens can be looked up directly.
+#[derive(Clone, Default, Debug)]
+pub struct SynonymDictionary {
+    words: HashMap<String, String>,
+    phrases: Vec<(Vec<String>, String)>,
+}
+
+impl SynonymDictionary {
+    pub fn new(groups: Vec<Vec<String>>, analyzer: &mut TextAnalyzer) -> SynonymDictionary {
+        let mut words: HashMap<String, String> = HashMap::new();
+        let mut phrases: Vec<(Vec<String>, String)> = vec![];
+        for group in groups {
+            let analyzed: Vec<Vec<String>> = group.iter()
+                .map(|x| analyze(analyzer, x))
+                .filter(|x| !x.is_empty())
+                .collect();
+            let Some(canonical) = analyzed.first().map(|x| x.concat()) else { continue };
+            for tokens in analyzed {
+                if tokens.len() == 1 {
+                    words.insert(tokens[0].clone(), canonical.clone());
+                } else {
+                    phrases.push((tokens, canonical.clone()));
+                }
+            }
+        }
+        phrases.sort_by_key(|x| std::cmp::Reverse(x.0.len()));
+        SynonymDictionary { words, phrases }
+    }
+
+    fn apply(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let texts: Vec<&str> = tokens.iter().map(|x| x.text.as_str()).collect();
+        let mut res: Vec<Token> = vec![];
+        for (i, token) in tokens.iter().enumerate() {
+            let phrase = self.phrases.iter()
+                .find(|(phrase_tokens, _)| texts[i..].starts_with(&phrase_tokens.iter().map(|x| x.as_str()).collect_vec()));
+            if let Some((phrase_tokens, canonical)) = phrase {
+                // keep the individual words searchable and add the whole phrase at the position of its first word
+                let last = &tokens[i + phrase_tokens.len() - 1];
+                res.push(Token {
+                    offset_from: token.offset_from,
+                    offset_to: last.offset_to,
+                    position: token.position,
+                    text: canonical.clone(),
+                    position_length: phrase_tokens.len(),
+                });
+            }
+            let mut cur = token.clone();
+            if let Some(canonical) = self.words.get(&cur.text) {
+                cur.text = canonical.clone();
+            }
+            res.push(cur);
+        }
+        res
+    }
+}
+
+fn analyze(analyzer: &mut TextAnalyzer, text: &str) -> Vec<String> {
+    let mut stream = analyzer.token_stream(text);
+    let mut res: Vec<String> = vec![];
+    while stream.advance() {
+        res.push(stream.token().text.clone());
+    }
+    res
 }
 
 
 #[derive(Clone)]
-pub struct SynonymFilter;
+pub struct SynonymFilter {
+    dictionary: Arc<RwLock<SynonymDictionary>>,
+}
+
+impl SynonymFilter {
+    pub fn new(dictionary: Arc<RwLock<SynonymDictionary>>) -> SynonymFilter {
+        SynonymFilter { dictionary }
+    }
+}
 
 impl TokenFilter for SynonymFilter {
     type Tokenizer<T: Tokenizer> = SynonymFilterWrapper<T>;
@@ -40,7 +88,7 @@ impl TokenFilter for SynonymFilter {
     fn transform<T: Tokenizer>(self, tokenizer: T) -> Self::Tokenizer<T> {
         SynonymFilterWrapper {
             inner: tokenizer,
-            buffer: String::new()
+            dictionary: self.dictionary,
         }
     }
 }
@@ -48,56 +96,92 @@ impl TokenFilter for SynonymFilter {
 #[derive(Clone)]
 pub struct SynonymFilterWrapper<T> {
     inner: T,
-    buffer: String,
-
+    dictionary: Arc<RwLock<SynonymDictionary>>,
 }
 
 impl<T: Tokenizer> Tokenizer for SynonymFilterWrapper<T> {
-    type TokenStream<'a> = SynonymFilterStream<'a, T::TokenStream<'a>>;
+    type TokenStream<'a> = SynonymFilterStream;
 
     fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        // phrases need to look ahead, so the whole stream is collected up front
+        let mut tail = self.inner.token_stream(text);
+        let mut tokens: Vec<Token> = vec![];
+        while tail.advance() {
+            tokens.push(tail.token().clone());
+        }
+        let dictionary = self.dictionary.read().expect("Synonym dictionary lock should not be poisoned");
         SynonymFilterStream {
-            tail: self.inner.token_stream(text),
-            buffer: &mut self.buffer,
+            tokens: dictionary.apply(tokens),
+            cursor: None,
         }
     }
 }
 
-pub struct SynonymFilterStream<'a, T> {
-    tail: T,
-    buffer: &'a mut String,
-
+pub struct SynonymFilterStream {
+    tokens: Vec<Token>,
+    cursor: Option<usize>,
 }
 
-impl<T: TokenStream> TokenStream for SynonymFilterStream<'_, T> {
+impl TokenStream for SynonymFilterStream {
     fn advance(&mut self) -> bool {
-        if !self.tail.advance() {
-            return false;
-        }
-        if let Some(res) = get_default_args().get(&self.token().text.clone()) {
-            // ignore its already ascii
-            mem::swap(&mut self.tail.token_mut().text, &mut res.clone());
-        }
-        true
+        let next = self.cursor.map(|x| x + 1).unwrap_or(0);
+        self.cursor = Some(next);
+        next < self.tokens.len()
     }
 
     fn token(&self) -> &Token {
-        self.tail.token()
+        &self.tokens[self.cursor.unwrap_or(0)]
     }
 
     fn token_mut(&mut self) -> &mut Token {
-        self.tail.token_mut()
+        &mut self.tokens[self.cursor.unwrap_or(0)]
     }
 }
 
 
-impl<T> SynonymFilterStream<'_, T> {
-    fn predicate(&self, token: &Token) -> bool {
-        if get_default_args().contains_key(&token.text) {
-            return false
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use tantivy::tokenizer::{LowerCaser, SimpleTokenizer, TextAnalyzer, TokenStream};
+
+    use crate::search::synonym_tokenizer::{SynonymDictionary, SynonymFilter};
+
+    fn analyzer_with(groups: Vec<Vec<&str>>) -> TextAnalyzer {
+        let mut base = TextAnalyzer::builder(SimpleTokenizer::default()).filter(LowerCaser).build();
+        let groups = groups.into_iter().map(|x| x.into_iter().map(|y| y.to_string()).collect()).collect();
+        let dictionary = SynonymDictionary::new(groups, &mut base);
+        TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .filter(SynonymFilter::new(Arc::new(RwLock::new(dictionary))))
+            .build()
+    }
+
+    fn tokens(analyzer: &mut TextAnalyzer, text: &str) -> Vec<(String, usize)> {
+        let mut stream = analyzer.token_stream(text);
+        let mut res = vec![];
+        while stream.advance() {
+            res.push((stream.token().text.clone(), stream.token().position));
         }
-        return true
+        res
+    }
+
+    #[test]
+    fn multi_way_group() {
+        let mut analyzer = analyzer_with(vec![vec!["courgette", "zucchini", "marrow"]]);
+        assert_eq!(vec![("courgette".to_string(), 0), ("courgette".to_string(), 1)], tokens(&mut analyzer, "Marrow zucchini"));
     }
-}
 
+    #[test]
+    fn phrase_adds_canonical_token() {
+        let mut analyzer = analyzer_with(vec![vec!["scallion", "spring onion"]]);
+        let res = tokens(&mut analyzer, "chopped spring onion");
+        assert_eq!(vec![("chopped".to_string(), 0), ("scallion".to_string(), 1), ("spring".to_string(), 1), ("onion".to_string(), 2)], res);
+    }
 
+    #[test]
+    fn unknown_words_untouched() {
+        let mut analyzer = analyzer_with(vec![vec!["courgette", "zucchini"]]);
+        assert_eq!(vec![("onion".to_string(), 0)], tokens(&mut analyzer, "onion"));
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+
+use crate::models::{QSeason, RecipeSeason};
+
+const MONTH_NAMES: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// 1-based like chrono's `month()`.
+pub fn month_name(month: u32) -> &'static str {
+    MONTH_NAMES.get((month as usize).wrapping_sub(1)).copied().unwrap_or("")
+}
+
+/// For month selects.
+pub fn months() -> Vec<(u32, &'static str)> {
+    (1..=12).map(|x| (x, month_name(x))).collect()
+}
+
+/// Inclusive, a range like November to February wraps around the new year.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MonthRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl MonthRange {
+    /// A single month when only one end is given, months outside 1 to 12 are ignored.
+    pub fn new(start: Option<i32>, end: Option<i32>) -> Option<MonthRange> {
+        let valid = |x: Option<i32>| x.filter(|y| (1..=12).contains(y)).map(|y| y as u32);
+        match (valid(start), valid(end)) {
+            (Some(start), Some(end)) => Some(MonthRange { start, end }),
+            (Some(x), None) | (None, Some(x)) => Some(MonthRange { start: x, end: x }),
+            (None, None) => None,
+        }
+    }
+
+    pub fn contains(&self, month: u32) -> bool {
+        if self.start <= self.end {
+            (self.start..=self.end).contains(&month)
+        } else {
+            month >= self.start || month <= self.end
+        }
+    }
+}
+
+impl fmt::Display for MonthRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", month_name(self.start))
+        } else {
+            write!(f, "{} - {}", month_name(self.start), month_name(self.end))
+        }
+    }
+}
+
+/// A row of the `season` table, seasons without months like "Independent" or "Ramadan" never match a date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Season {
+    pub season_id: i32,
+    pub name: String,
+    pub months: Option<MonthRange>,
+    /// Recipes filed under it can be cooked at any time, like "Independent".
+    pub all_year: bool,
+}
+
+impl From<&QSeason> for Season {
+    fn from(a: &QSeason) -> Self {
+        Season {
+            season_id: a.season_id.unwrap(),
+            name: a.tag_name.clone().unwrap_or_default(),
+            months: MonthRange::new(a.start_month, a.end_month),
+            all_year: a.all_year,
+        }
+    }
+}
+
+impl Season {
+    pub fn start_month(&self) -> Option<u32> {
+        self.months.map(|x| x.start)
+    }
+
+    pub fn end_month(&self) -> Option<u32> {
+        self.months.map(|x| x.end)
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// In the order the seasons are listed.
+pub fn seasons_in_month(seasons: &[Season], month: u32) -> Vec<&Season> {
+    seasons.iter().filter(|x| x.months.is_some_and(|y| y.contains(month))).collect()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SeasonEntry {
+    pub season_id: i32,
+    pub months: Option<MonthRange>,
+}
+
+impl SeasonEntry {
+    /// Without months of its own the months of the season count.
+    pub fn matches_month(&self, month: u32, seasons: &[Season]) -> bool {
+        match self.months {
+            Some(months) => months.contains(month),
+            None => seasons.iter().any(|x| x.season_id == self.season_id && x.months.is_some_and(|y| y.contains(month))),
+        }
+    }
+
+    /// Like [`SeasonEntry::matches_month`], but all-year recipes are in season at any time.
+    pub fn in_season(&self, month: u32, seasons: &[Season]) -> bool {
+        (self.months.is_none() && seasons.iter().any(|x| x.season_id == self.season_id && x.all_year)) || self.matches_month(month, seasons)
+    }
+}
+
+/// The primary season first, rows of other recipes are ignored.
+pub fn recipe_season_entries(primary_season: i32, rows: &[RecipeSeason]) -> Vec<SeasonEntry> {
+    let entry = |x: &RecipeSeason| SeasonEntry { season_id: x.season_id, months: MonthRange::new(x.start_month, x.end_month) };
+    let primary = rows.iter()
+        .find(|x| x.season_id == primary_season)
+        .map(entry)
+        .unwrap_or(SeasonEntry { season_id: primary_season, months: None });
+    let mut res = vec![primary];
+    res.extend(rows.iter().filter(|x| x.season_id != primary_season).map(entry));
+    res
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::models::RecipeSeason;
+    use crate::seasonality::{recipe_season_entries, seasons_in_month, MonthRange, Season, SeasonEntry};
+
+    fn row(season_id: i32, start_month: Option<i32>, end_month: Option<i32>) -> RecipeSeason {
+        RecipeSeason { recipe_id: 1, season_id, start_month, end_month, created_at: None }
+    }
+
+    #[test]
+    fn ranges_wrap_around_the_year() {
+        let winter = MonthRange::new(Some(11), Some(2)).unwrap();
+        assert!(winter.contains(12) && winter.contains(1) && winter.contains(2));
+        assert!(!winter.contains(3) && !winter.contains(10));
+        assert!(MonthRange::new(Some(6), Some(8)).unwrap().contains(7));
+        assert_eq!("Nov - Feb", winter.to_string());
+        assert_eq!(Some(MonthRange { start: 5, end: 5 }), MonthRange::new(None, Some(5)));
+        assert_eq!(None, MonthRange::new(Some(13), None));
+    }
+
+    #[test]
+    fn primary_season_comes_first() {
+        let res = recipe_season_entries(1, &[row(3, None, None), row(1, Some(6), Some(7))]);
+        assert_eq!(vec![
+            SeasonEntry { season_id: 1, months: Some(MonthRange { start: 6, end: 7 }) },
+            SeasonEntry { season_id: 3, months: None },
+        ], res);
+        assert_eq!(vec![SeasonEntry { season_id: 2, months: None }], recipe_season_entries(2, &[]));
+    }
+
+    fn seasons() -> Vec<Season> {
+        vec![
+            Season { season_id: 1, name: "Summer".to_string(), months: MonthRange::new(Some(6), Some(8)), all_year: false },
+            Season { season_id: 3, name: "Winter".to_string(), months: MonthRange::new(Some(12), Some(2)), all_year: false },
+            Season { season_id: 5, name: "Independent".to_string(), months: None, all_year: true },
+            Season { season_id: 6, name: "Christmas".to_string(), months: MonthRange::new(Some(12), None), all_year: false },
+        ]
+    }
+
+    #[test]
+    fn seasons_of_a_month() {
+        let seasons = seasons();
+        let names = |month: u32| seasons_in_month(&seasons, month).into_iter().map(|x| x.name.as_str()).collect::<Vec<&str>>();
+        assert_eq!(vec!["Winter", "Christmas"], names(12));
+        assert_eq!(vec!["Winter"], names(1));
+        assert!(names(4).is_empty());
+    }
+
+    #[test]
+    fn months_narrow_the_season() {
+        let seasons = seasons();
+        let summer = SeasonEntry { season_id: 1, months: None };
+        assert!(summer.matches_month(7, &seasons) && !summer.matches_month(9, &seasons));
+        let early_summer = SeasonEntry { months: MonthRange::new(Some(5), Some(6)), ..summer };
+        assert!(early_summer.matches_month(5, &seasons) && !early_summer.matches_month(7, &seasons));
+        assert!(!SeasonEntry { season_id: 5, months: None }.matches_month(7, &seasons));
+        assert!(SeasonEntry { season_id: 5, months: None }.in_season(7, &seasons));
+        assert!(!SeasonEntry { season_id: 6, months: None }.in_season(7, &seasons));
+        assert!(!SeasonEntry { season_id: 5, months: None }.in_season(7, &seasons[..2]));
+    }
+}
@@ -1,3 +1,4 @@
+use itertools::Itertools;
 use regex::Regex;
 
 pub fn extract_domain(a: String) -> String {
@@ -9,10 +10,19 @@ pub fn extract_domain(a: String) -> String {
     return c.to_string();
 }
 
+/// Tags are entered comma separated, quotes are dropped because tags end up in search queries.
+pub fn parse_tags(a: &str) -> Vec<String> {
+    a.split(',')
+        .map(|x| x.replace('"', "").trim().to_lowercase())
+        .filter(|x| !x.is_empty())
+        .unique()
+        .collect()
+}
+
 
 #[cfg(test)]
 mod tests {
-    use crate::strops::extract_domain;
+    use crate::strops::{extract_domain, parse_tags};
 
     #[test]
     fn guardian() {
@@ -28,4 +38,10 @@ mod tests {
         assert_eq!("cooking.nytimes.com", extract_domain(url))
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn tags() {
+        assert_eq!(vec!["vegetarian", "quick", "bbq light"], parse_tags(" Vegetarian, quick,,QUICK , BBQ \"light\""));
+        assert!(parse_tags(" , ").is_empty());
+    }
+}
@@ -3,8 +3,21 @@ use std::collections::{HashMap, HashSet};
 use askama::Template;
 
 use crate::args::{RecipePrefill, SearchPrefill};
+use crate::cook::StepTimer;
+use crate::cost::{CostEstimate, CurrentPrice, EPriceUnit};
+use crate::diet::{EDiet, EIngredientAttribute, RecipeDiet};
+use crate::markdown::ESection;
+use crate::nutrition::NutritionEstimate;
 use crate::models::*;
-use crate::parsetypes::ESeason;
+use crate::print::{EPaperFormat, PrintRecipe};
+use crate::produce::ProduceConfig;
+use crate::rating::RatingSummary;
+use crate::recipe_links::{ComponentRecipe, ELinkType, LinkedRecipe};
+use crate::recipe_time::EDifficulty;
+use crate::revision::RevisionEntry;
+use crate::search::facets::SearchFacets;
+use crate::search::language::ERecipeLanguage;
+use crate::seasonality::{MonthRange, Season};
 
 // bring trait in scope
 
@@ -15,9 +28,11 @@ pub struct HelloTemplate<'a> {
     // the name of the struct can be anything
     pub name: &'a str,
     pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
     // the field name should match the variable name
     pub title: &'a str,
     pub user_id: Option<i32>,
+    pub is_admin: bool,
     pub build_version: &'a str,
     pub debug_compilation: bool,
     // in your template
@@ -35,18 +50,24 @@ impl<'a> HelloTemplate<'a> {
 // to the `templates` dir in the crate root
 pub struct CourseTemplate<'a> {
     pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
     pub books: &'a Vec<QBook>,
     pub course_name: &'a str,
-    pub seasons: Vec<ESeason>,
+    pub seasons: Vec<Season>,
     pub title: &'a str,
     pub tried: HashSet<i32>,
     pub logged_in: bool,
     pub recipes_to_ingredients: HashMap<i32, Vec<String>>,
     pub user_id: Option<i32>,
-    pub recipes_by_season_and_source: Vec<(ESeason, Vec<(String, Vec<FullRecipe>)>)>,
+    pub recipes_by_season_and_source: Vec<(Season, Vec<(String, Vec<FullRecipe>)>)>,
     pub build_version: &'a str,
     pub commented: HashSet<i32>,
     pub texted: HashSet<i32>,
+    pub collections: Vec<QCollection>,
+    pub recipes_to_tags: HashMap<i32, Vec<String>>,
+    pub recipes_to_diets: HashMap<i32, Vec<EDiet>>,
+    pub ratings: HashMap<i32, RatingSummary>,
+    pub covers: HashMap<i32, RecipeImage>,
     pub debug_compilation: bool,
 
 
@@ -65,7 +86,9 @@ impl<'a> CourseTemplate<'a> {
 // to the `templates` dir in the crate root
 pub struct BookForm<'a> {
     pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
     pub title: &'a str,
+    pub languages: Vec<ERecipeLanguage>,
     pub user_id: Option<i32>,
     pub build_version: &'a str,
     pub debug_compilation: bool,
@@ -84,8 +107,11 @@ impl<'a> BookForm<'a> {
 #[template(path = "add_recipe.html")] // using the template in this path, relative
 pub struct RecipeForm<'a> {
     pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
     pub books: &'a Vec<QBook>,
-    pub seasons: Vec<ESeason>,
+    pub seasons: Vec<Season>,
+    pub languages: Vec<ERecipeLanguage>,
+    pub difficulties: Vec<EDifficulty>,
     pub prefill: RecipePrefill,
     pub title: &'a str,
     pub newest: Option<FullRecipe>,
@@ -108,8 +134,9 @@ impl<'a> RecipeForm<'a> {
 #[template(path = "search_form.html")] // using the template in this path, relative
 pub struct SearchForm<'a> {
     pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
     pub books: &'a Vec<QBook>,
-    pub seasons: Vec<ESeason>,
+    pub seasons: Vec<Season>,
     pub recipes: Option<Vec<FullRecipe>>,
     pub title: &'a str,
     pub recipes_to_ingredients: HashMap<i32, Vec<String>>,
@@ -120,6 +147,17 @@ pub struct SearchForm<'a> {
     pub commented: HashSet<i32>,
     pub texted: HashSet<i32>,
     pub tried_ids: HashSet<i32>,
+    pub facets: Option<SearchFacets>,
+    pub suggestion: Option<String>,
+    pub collection: Option<SavedSearch>,
+    pub collections: Vec<QCollection>,
+    pub tags: Vec<String>,
+    pub diets: Vec<EDiet>,
+    pub difficulties: Vec<EDifficulty>,
+    pub recipes_to_diets: HashMap<i32, Vec<EDiet>>,
+    pub ratings: HashMap<i32, RatingSummary>,
+    pub covers: HashMap<i32, RecipeImage>,
+    pub accompaniments: Vec<(i32, String)>,
     pub debug_compilation: bool,
 
     // in your template
@@ -135,6 +173,7 @@ impl<'a> SearchForm<'a> {
 #[template(path = "login.html")] // using the template in this path, relative
 pub struct LoginPage<'a> {
     pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
     pub title: &'a str,
     pub user_id: Option<i32>,
     pub build_version: &'a str,
@@ -154,13 +193,17 @@ impl<'a> LoginPage<'a> {
 #[template(path = "recipe_edit_form.html")] // using the template in this path, relative
 pub struct RecipeEditForm<'a> {
     pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
     pub recipe: &'a FullRecipe,
     pub ingredients: String,
     pub title: &'a str,
     pub books: &'a Vec<QBook>,
-    pub seasons: Vec<ESeason>,
-    pub prefill_season: usize,
+    pub seasons: Vec<Season>,
+    pub prefill_season: i32,
+    pub languages: Vec<ERecipeLanguage>,
+    pub difficulties: Vec<EDifficulty>,
     pub recipe_text: String,
+    pub tags: String,
     pub user_id: Option<i32>,
     pub build_version: &'a str,
     pub debug_compilation: bool,
@@ -178,21 +221,113 @@ mod filters {
     pub fn asref<'a, T>(s: &'a T) -> askama::Result<&'a T> { //https://github.com/djc/askama/issues/330
         Ok(s)
     }
+
+    pub fn render_markdown<T: AsRef<str>>(s: T) -> askama::Result<String> {
+        Ok(crate::markdown::render_markdown(s.as_ref()))
+    }
+
+    /// `{{ name|thumbnail("m") }}`, unknown sizes fall back to the original.
+    pub fn thumbnail<T: AsRef<str>>(s: T, size: &str) -> askama::Result<String> {
+        Ok(match crate::upload::EThumbnailSize::from_code(size) {
+            Some(x) => crate::upload::thumbnail_name(s.as_ref(), x),
+            None => s.as_ref().to_string(),
+        })
+    }
+}
+
+#[derive(Template)]
+#[template(path = "cook_mode.html")]
+pub struct CookMode<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub recipe_id: i32,
+    pub steps: Vec<(String, Vec<StepTimer>)>,
+    pub ingredients_html: Option<String>,
+    pub ingredients: Vec<String>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> CookMode<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+/// Standalone page without navigation, sized for printing.
+#[derive(Template)]
+#[template(path = "print_card.html")]
+pub struct PrintCard {
+    pub recipe_id: i32,
+    pub recipe: PrintRecipe,
+    pub recipe_sections: Vec<(Option<ESection>, String)>,
+    pub has_ingredient_section: bool,
+    pub format: EPaperFormat,
+    pub formats: Vec<EPaperFormat>,
+}
+
+impl PrintCard {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+/// Live preview on the edit form, rendered the same way as the detail page.
+#[derive(Template)]
+#[template(path = "recipe_text.html")]
+pub struct RecipeTextPreview {
+    pub recipe_sections: Vec<(Option<ESection>, String)>,
+}
+
+impl RecipeTextPreview {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
 }
 
 #[derive(Template)] // this will generate the code...
 #[template(path = "recipe_detail.html")] // using the template in this path, relative
 pub struct RecipeDetail<'a> {
     pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
     pub course: &'a str,
     pub recipe: &'a FullRecipe,
     pub ingredients: Vec<String>,
     pub title: &'a str,
     pub book_name: &'a Option<String>,
-    pub season: ESeason,
+    pub seasons: Vec<(Season, Option<MonthRange>)>,
+    pub season_options: Vec<(Season, bool, Option<u32>, Option<u32>)>,
+    pub months: Vec<(u32, &'static str)>,
+    pub language: ERecipeLanguage,
     pub tried: bool,
-    pub comments: Vec<Comment>,
+    pub comments: Vec<(Comment, usize, String)>,
+    pub is_admin: bool,
     pub recipe_text: String,
+    pub recipe_sections: Vec<(Option<ESection>, String)>,
+    pub tags: Vec<String>,
+    pub collections: Vec<QCollection>,
+    pub recipe_collections: Vec<QCollection>,
+    pub diet: RecipeDiet,
+    pub diet_overrides: Vec<(EDiet, Option<bool>)>,
+    pub rating_summary: RatingSummary,
+    pub own_rating: Option<RecipeRating>,
+    pub cooking_log: Vec<(Cooked, String)>,
+    pub images: Vec<RecipeImage>,
+    pub revisions: Vec<RevisionEntry>,
+    pub links: Vec<(&'static str, Vec<(LinkedRecipe, String)>)>,
+    pub components: Vec<ComponentRecipe>,
+    pub nutrition: NutritionEstimate,
+    pub cost: CostEstimate,
+    pub quantities: Vec<(i32, String, Option<f32>)>,
+    pub times: String,
+    pub difficulty: Option<EDifficulty>,
+    pub equipment_options: Vec<(Equipment, bool)>,
+    pub required_equipment: Vec<(String, bool)>,
+    pub link_types: Vec<ELinkType>,
+    pub recipe_choices: Vec<(i32, String)>,
+    pub today: String,
     pub user_id: Option<i32>,
     pub build_version: &'a str,
     pub debug_compilation: bool,
@@ -203,4 +338,264 @@ impl<'a> RecipeDetail<'a> {
     pub fn get(&self) -> String {
         return self.render().unwrap();
     }
-}
\ No newline at end of file
+}
+
+#[derive(Template)]
+#[template(path = "admin_synonyms.html")]
+pub struct SynonymAdmin<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub groups: Vec<(i32, String)>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> SynonymAdmin<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin_tags.html")]
+pub struct TagAdmin<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub ingredients: Vec<(String, usize)>,
+    pub tags: Vec<(String, usize)>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> TagAdmin<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin_ingredients.html")]
+pub struct IngredientAdmin<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub attributes: Vec<EIngredientAttribute>,
+    pub ingredients: Vec<(Ingredient, HashSet<EIngredientAttribute>, Option<String>)>,
+    pub foods: Vec<String>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> IngredientAdmin<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin_seasons.html")]
+pub struct SeasonAdmin<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub seasons: Vec<(Season, i32, bool)>,
+    pub months: Vec<(u32, &'static str)>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> SeasonAdmin<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin_equipment.html")]
+pub struct EquipmentAdmin<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub equipment: Vec<(Equipment, usize)>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> EquipmentAdmin<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin_prices.html")]
+pub struct PriceAdmin<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub units: Vec<EPriceUnit>,
+    pub currency: String,
+    pub today: String,
+    pub prices: Vec<(Ingredient, Option<CurrentPrice>)>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> PriceAdmin<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin_produce.html")]
+pub struct ProduceAdmin<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub region: String,
+    pub regions: Vec<String>,
+    pub config: ProduceConfig,
+    pub months: Vec<(u32, &'static str)>,
+    pub ingredients: Vec<(Ingredient, HashSet<u32>)>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> ProduceAdmin<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "in_season.html")]
+pub struct InSeason<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub month: &'a str,
+    pub season: String,
+    pub config: ProduceConfig,
+    pub produce: Vec<String>,
+    pub entries: Vec<(FullRecipe, Vec<String>, usize)>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> InSeason<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "top_rated.html")]
+pub struct TopRated<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub season: String,
+    pub entries: Vec<(FullRecipe, RatingSummary)>,
+    pub own_ratings: HashMap<i32, RecipeRating>,
+    pub last_cooked: HashMap<i32, String>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> TopRated<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "history.html")]
+pub struct History<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub entries: Vec<(String, Vec<(Cooked, FullRecipe)>)>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> History<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "settings.html")]
+pub struct Settings<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub email: String,
+    pub display_name: String,
+    pub equipment: Vec<(Equipment, bool)>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> Settings<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "collections.html")]
+pub struct CollectionList<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub own: Vec<QCollection>,
+    pub shared: Vec<(QCollection, String)>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> CollectionList<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "collection_detail.html")]
+pub struct CollectionDetail<'a> {
+    pub courses: &'a Vec<QCourse>,
+    pub saved_searches: Vec<SavedSearch>,
+    pub title: &'a str,
+    pub collection: QCollection,
+    pub entries: Vec<(FullRecipe, Option<String>)>,
+    pub can_edit: bool,
+    pub owner_email: String,
+    pub shared_with: Vec<String>,
+    pub id_to_book_name: HashMap<i32, String>,
+    pub user_id: Option<i32>,
+    pub build_version: &'a str,
+    pub debug_compilation: bool,
+}
+
+impl<'a> CollectionDetail<'a> {
+    pub fn get(&self) -> String {
+        self.render().unwrap()
+    }
+}
@@ -1,91 +1,186 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use itertools::Itertools;
-use tantivy::schema::{Facet, FacetOptions, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, STORED};
-use tantivy::tokenizer::{AsciiFoldingFilter, Language, LowerCaser, SimpleTokenizer, Stemmer, TextAnalyzer};
+use tantivy::schema::{Facet, FacetOptions, Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, FAST, INDEXED, STORED};
+use tantivy::tokenizer::{AsciiFoldingFilter, Language, LowerCaser, SimpleTokenizer, Stemmer, StopWordFilter, TextAnalyzer};
 use tantivy::{Document, Index, IndexWriter, TantivyDocument, Term};
 use tokio::sync::Mutex;
 
 use crate::args::SearchPrefill;
-use crate::parsetypes::ESeason;
+use crate::diet::EDiet;
 use crate::queries::RecipeQueryResult;
-use crate::search::synonym_tokenizer::SynonymFilter;
+use crate::recipe_time::{effective_minutes, EDifficulty};
+use crate::search::language::ERecipeLanguage;
+use crate::search::synonym_tokenizer::{SynonymDictionary, SynonymFilter};
+use crate::seasonality::Season;
 
 #[derive(Clone)]
 pub struct SearchState {
     pub index: Index,
-    writer: Arc<Mutex<IndexWriter>>
+    writer: Arc<Mutex<IndexWriter>>,
+    // one per language, since the dictionary has to be stemmed like the text it is applied to
+    synonyms: HashMap<ERecipeLanguage, Arc<RwLock<SynonymDictionary>>>,
 }
 
-pub fn setup_search_state() -> tantivy::Result<SearchState> {
+pub fn setup_search_state(synonym_groups: Vec<Vec<String>>) -> tantivy::Result<SearchState> {
     let schema = build_schema();
 
 
     let index = Index::builder().schema(schema.clone()).create_from_tempdir()?;
-    let tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
-        .filter(LowerCaser)
-        .filter(AsciiFoldingFilter)
-        .filter(Stemmer::new(Language::English))
-        .filter(SynonymFilter)
-        .build();
-    index.tokenizers()
-        .register("ascii", tokenizer);
+    let mut synonyms: HashMap<ERecipeLanguage, Arc<RwLock<SynonymDictionary>>> = HashMap::new();
+    for language in ERecipeLanguage::get_languages() {
+        let dictionary = Arc::new(RwLock::new(SynonymDictionary::new(synonym_groups.clone(), &mut build_base_analyzer(language))));
+        index.tokenizers()
+            .register(language.tokenizer_name().as_str(), build_language_analyzer(language, dictionary.clone()));
+        synonyms.insert(language, dictionary);
+    }
     return Ok(SearchState {
         index: index.clone(),
-        writer: Arc::new(Mutex::new(index.clone().writer(INDEX_MEMORY).unwrap()))
-
+        writer: Arc::new(Mutex::new(index.clone().writer(INDEX_MEMORY).unwrap())),
+        synonyms,
     });
 }
 
+fn build_language_analyzer(language: ERecipeLanguage, synonyms: Arc<RwLock<SynonymDictionary>>) -> TextAnalyzer {
+    if language == ERecipeLanguage::English {
+        return TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .filter(AsciiFoldingFilter)
+            .filter(Stemmer::new(Language::English))
+            .filter(SynonymFilter::new(synonyms))
+            .build();
+    }
+    // stop word lists are spelled with accents, so they have to be removed before folding
+    TextAnalyzer::builder(SimpleTokenizer::default())
+        .filter(LowerCaser)
+        .filter(StopWordFilter::new(language.stemmer_language()).expect("Stop words should exist for all recipe languages"))
+        .filter(AsciiFoldingFilter)
+        .filter(Stemmer::new(language.stemmer_language()))
+        .filter(SynonymFilter::new(synonyms))
+        .build()
+}
+
+/// The analyzer of a language without synonyms, used to normalise the synonym dictionary itself.
+fn build_base_analyzer(language: ERecipeLanguage) -> TextAnalyzer {
+    if language == ERecipeLanguage::English {
+        return TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .filter(AsciiFoldingFilter)
+            .filter(Stemmer::new(Language::English))
+            .build();
+    }
+    TextAnalyzer::builder(SimpleTokenizer::default())
+        .filter(LowerCaser)
+        .filter(StopWordFilter::new(language.stemmer_language()).expect("Stop words should exist for all recipe languages"))
+        .filter(AsciiFoldingFilter)
+        .filter(Stemmer::new(language.stemmer_language()))
+        .build()
+}
+
+/// Swaps the dictionaries used by the analyzers, the index has to be rebuilt afterwards.
+pub fn reload_synonyms(search_state: &SearchState, synonym_groups: Vec<Vec<String>>) {
+    for (language, synonyms) in search_state.synonyms.iter() {
+        let dictionary = SynonymDictionary::new(synonym_groups.clone(), &mut build_base_analyzer(*language));
+        *synonyms.write().expect("Synonym dictionary lock should not be poisoned") = dictionary;
+    }
+}
+
 pub const SCHEMA_TITLE: &'static str = "title";
 
 pub const SCHEMA_BODY: &'static str = "body";
 
 const SCHEMA_URL: &'static str = "url";
 
-const SCHEMA_BOOK: &'static str = "book";
+pub const SCHEMA_BOOK: &'static str = "book";
 
-const SCHEMA_SEASON: &'static str = "season";
+pub const SCHEMA_SEASON: &'static str = "season";
 
-const SCHEMA_COURSE: &'static str = "course";
+pub const SCHEMA_COURSE: &'static str = "course";
+
+pub const SCHEMA_COLLECTION: &'static str = "collection";
+
+pub const SCHEMA_DIET: &'static str = "diet";
 
 pub const SCHEMA_RECIPE_ID: &'static str = "recipe_id";
 
 pub const SCHEMA_INGREDIENTS: &'static str = "ingredients";
 
+pub const SCHEMA_TAGS: &'static str = "tags";
+
+pub const SCHEMA_MINUTES: &str = "minutes";
+
+pub const SCHEMA_DIFFICULTY: &str = "difficulty";
+
+/// Title and ingredient words as typed, the vocabulary for "did you mean" suggestions.
+pub const SCHEMA_WORDS: &str = "words";
+
+/// Tantivy's built-in tokenizer, lower cased but not stemmed.
+pub const WORDS_TOKENIZER: &str = "default";
 
-fn build_schema() -> Schema {
-    let mut schema_builder = Schema::builder();
 
+fn build_text_options(language: ERecipeLanguage) -> TextOptions {
     let text_field_indexing = TextFieldIndexing::default()
-        .set_tokenizer("ascii")
+        .set_tokenizer(language.tokenizer_name().as_str())
         .set_index_option(IndexRecordOption::WithFreqsAndPositions);
-    let text_options = TextOptions::default()
+    TextOptions::default()
         .set_indexing_options(text_field_indexing)
-        .set_stored();
-    schema_builder.add_text_field(SCHEMA_TITLE, text_options.clone());
-    schema_builder.add_text_field(SCHEMA_BODY, text_options.clone());
+        .set_stored()
+}
+
+fn build_schema() -> Schema {
+    let mut schema_builder = Schema::builder();
+
+    let text_options = build_text_options(ERecipeLanguage::English);
+    for language in ERecipeLanguage::get_languages() {
+        schema_builder.add_text_field(language.field_name(SCHEMA_TITLE).as_str(), build_text_options(language));
+        schema_builder.add_text_field(language.field_name(SCHEMA_BODY).as_str(), build_text_options(language));
+    }
     schema_builder.add_text_field(SCHEMA_INGREDIENTS, text_options.clone());
+    schema_builder.add_text_field(SCHEMA_TAGS, text_options.clone());
     schema_builder.add_text_field(SCHEMA_URL, text_options.clone());
-    schema_builder.add_i64_field(SCHEMA_RECIPE_ID, STORED);
+    schema_builder.add_text_field(SCHEMA_WORDS, TextOptions::default()
+        .set_indexing_options(TextFieldIndexing::default().set_tokenizer(WORDS_TOKENIZER).set_index_option(IndexRecordOption::Basic)));
+    // indexed as well, so updates can delete the previous document of a recipe
+    schema_builder.add_i64_field(SCHEMA_RECIPE_ID, STORED | INDEXED);
+    schema_builder.add_i64_field(SCHEMA_MINUTES, INDEXED | FAST);
+    schema_builder.add_i64_field(SCHEMA_DIFFICULTY, INDEXED | FAST);
     schema_builder.add_facet_field(SCHEMA_BOOK, FacetOptions::default());
     schema_builder.add_facet_field(SCHEMA_SEASON, FacetOptions::default());
     schema_builder.add_facet_field(SCHEMA_COURSE, FacetOptions::default());
+    schema_builder.add_facet_field(SCHEMA_COLLECTION, FacetOptions::default());
+    schema_builder.add_facet_field(SCHEMA_DIET, FacetOptions::default());
     schema_builder.build()
 }
 
+/// Title and body in every language plus ingredients and tags, which are always entered in English.
+pub fn text_search_fields(schema: &Schema) -> Vec<Field> {
+    let mut fields: Vec<Field> = ERecipeLanguage::get_languages().iter()
+        .flat_map(|x| vec![x.field_name(SCHEMA_TITLE), x.field_name(SCHEMA_BODY)])
+        .map(|x| schema.get_field(x.as_str()).unwrap())
+        .collect();
+    fields.push(schema.get_field(SCHEMA_INGREDIENTS).unwrap());
+    fields.push(schema.get_field(SCHEMA_TAGS).unwrap());
+    fields
+}
+
 
 const INDEX_MEMORY: usize = 50_000_000;
 
 pub fn update_index(search_state: &SearchState, recipe: RecipeQueryResult) {
+    update_index_with_recipes(search_state, vec![recipe]);
+}
+
+/// Replaces the given recipes in a single commit, the rest of the index stays as it is.
+pub fn update_index_with_recipes(search_state: &SearchState, recipes: Vec<RecipeQueryResult>) {
     let schema = search_state.index.schema();
     let mut index_writer = futures::executor::block_on(search_state.writer.lock());
-    let id_term = Term::from_field_i64(schema.get_field(SCHEMA_RECIPE_ID).expect("ID should exist"), recipe.recipe.recipe_id.expect("Recipe should have an id") as i64);
-    index_writer.delete_term(id_term);
-    let season_ids_to_seasons = ESeason::to_map();
-    let doc = recipe_to_doc(schema.clone(), season_ids_to_seasons.clone(), &recipe);
-    index_writer.add_document(doc).expect("Adding should work");
+    for recipe in recipes {
+        let id_term = Term::from_field_i64(schema.get_field(SCHEMA_RECIPE_ID).expect("ID should exist"), recipe.recipe.recipe_id.expect("Recipe should have an id") as i64);
+        index_writer.delete_term(id_term);
+        let doc = recipe_to_doc(schema.clone(), &recipe);
+        index_writer.add_document(doc).expect("Adding should work");
+    }
     index_writer.commit().expect("Commiting should work!");
 }
 
@@ -94,25 +189,38 @@ pub fn nuke_and_rebuild_with_recipes(search_state: &SearchState, recipes: Vec<Re
     let schema = search_state.index.schema();
     let mut index_writer = futures::executor::block_on(search_state.writer.lock());
     index_writer.delete_all_documents().expect("Writer access should be there");
-    let season_ids_to_seasons = ESeason::to_map();
     for enriched_recipe in recipes {
-        let doc = recipe_to_doc(schema.clone(), season_ids_to_seasons.clone(), &enriched_recipe);
+        let doc = recipe_to_doc(schema.clone(), &enriched_recipe);
 
         index_writer.add_document(doc).expect("Writing should still work");
     }
     index_writer.commit().expect("Commit should work");
 }
 
-fn recipe_to_doc(schema: Schema, season_ids_to_seasons: HashMap<usize, ESeason>, enriched_recipe: &RecipeQueryResult) -> TantivyDocument {
+fn recipe_to_doc(schema: Schema, enriched_recipe: &RecipeQueryResult) -> TantivyDocument {
     let mut doc = TantivyDocument::default();
+    let language = enriched_recipe.language;
     if let Some(i) = enriched_recipe.recipe.recipe_name.clone() {
-        doc.add_text(schema.get_field(SCHEMA_TITLE).unwrap(), i);
+        doc.add_text(schema.get_field(SCHEMA_WORDS).unwrap(), i.as_str());
+        doc.add_text(schema.get_field(language.field_name(SCHEMA_TITLE).as_str()).unwrap(), i);
     }
     for ingredient_name in enriched_recipe.ingredients.clone() {
+        doc.add_text(schema.get_field(SCHEMA_WORDS).unwrap(), ingredient_name.as_str());
         doc.add_text(schema.get_field(SCHEMA_INGREDIENTS).unwrap(), ingredient_name);
     }
+    for tag_name in enriched_recipe.tags.iter() {
+        doc.add_text(schema.get_field(SCHEMA_TAGS).unwrap(), tag_name);
+    }
     doc.add_i64(schema.get_field(SCHEMA_RECIPE_ID).unwrap(), enriched_recipe.recipe.recipe_id.unwrap() as i64);
     doc.add_facet(schema.get_field(SCHEMA_COURSE).unwrap(), Facet::from(format!("/course/{}", enriched_recipe.course_name).as_str()));
+    let cur_recipe = &enriched_recipe.recipe;
+    // recipes without a time or difficulty only show up when those filters are not used
+    if let Some(minutes) = effective_minutes(cur_recipe.prep_minutes, cur_recipe.cook_minutes, cur_recipe.total_minutes) {
+        doc.add_i64(schema.get_field(SCHEMA_MINUTES).unwrap(), minutes as i64);
+    }
+    if let Some(difficulty) = cur_recipe.difficulty.as_deref().and_then(EDifficulty::from_code) {
+        doc.add_i64(schema.get_field(SCHEMA_DIFFICULTY).unwrap(), difficulty.rank());
+    }
 
 
     if let Some(name_string) = enriched_recipe.book_name.clone() {
@@ -120,64 +228,98 @@ fn recipe_to_doc(schema: Schema, season_ids_to_seasons: HashMap<usize, ESeason>,
     }
 
     if let Some(i) = enriched_recipe.recipe_text.clone() {
-        doc.add_text(schema.get_field(SCHEMA_BODY).unwrap(), i);
+        doc.add_text(schema.get_field(language.field_name(SCHEMA_BODY).as_str()).unwrap(), i);
+    }
+    // seasons can be renamed, so like collections the facet holds the id
+    for cur_season_id in enriched_recipe.season_ids.iter() {
+        doc.add_facet(schema.get_field(SCHEMA_SEASON).unwrap(), Facet::from(format!("/season/{}", cur_season_id).as_str()));
+    }
+    // collection names are only unique per user, so the facet holds the id
+    for cur_collection_id in enriched_recipe.collection_ids.iter() {
+        doc.add_facet(schema.get_field(SCHEMA_COLLECTION).unwrap(), Facet::from(format!("/collection/{}", cur_collection_id).as_str()));
+    }
+    for diet in enriched_recipe.diets.iter() {
+        doc.add_facet(schema.get_field(SCHEMA_DIET).unwrap(), Facet::from(format!("/diet/{}", diet.code()).as_str()));
     }
-    let season_name = season_ids_to_seasons.get(&(enriched_recipe.recipe.primary_season as usize)).map(|x| x.to_string()).unwrap();
-    doc.add_facet(schema.get_field(SCHEMA_SEASON).unwrap(), Facet::from(format!("/season/{}", season_name.as_str()).as_str()));
     doc
 }
 
 pub fn search() {}
 
-pub fn build_query(options: SearchPrefill, book_names: HashMap<i32, String>, season_names: HashMap<usize, ESeason>, course_names: HashMap<i32, String>) -> String {
+pub fn build_query(options: SearchPrefill, book_names: HashMap<i32, String>, seasons: &[Season], course_names: HashMap<i32, String>) -> String {
     let mut parts: Vec<String> = vec![];
     if let Some(name_query) = options.clone().name.filter(|x| !x.trim().is_empty()) {
         name_query.split(" ").into_iter().for_each(|x| parts.push(format!("+{}", x)));
     }
+    parts.push(build_filter_query(options, book_names, seasons, course_names));
+
+    parts.join(" ")
+
+}
 
+pub fn build_filter_query(options: SearchPrefill, book_names: HashMap<i32, String>, seasons: &[Season], course_names: HashMap<i32, String>) -> String {
+    let mut parts: Vec<String> = vec![];
     if let Some(i) = book_names.get(&options.clone().book.unwrap_or(-1)) {
         parts.push(format!("+book:\"/book/{}\"", i))
     }
-    if let Some(season_term) = build_season_term(options.clone(), season_names) {
+    if let Some(season_term) = build_season_term(options.clone(), seasons) {
         parts.push(season_term)
     }
     if let Some(i) = course_names.get(&options.course.unwrap_or(-1)) {
         parts.push(format!("+course:/course/{}", i))
     }
+    if let Some(i) = options.collection.filter(|x| *x >= 0) {
+        parts.push(format!("+collection:/collection/{}", i))
+    }
+    if let Some(i) = options.tag.as_ref().map(|x| x.replace('"', "")).filter(|x| !x.trim().is_empty()) {
+        parts.push(format!("+tags:\"{}\"", i))
+    }
+    for diet in EDiet::get_diets().into_iter().filter(|x| options.has_diet(*x)) {
+        parts.push(format!("+diet:/diet/{}", diet.code()))
+    }
+    if options.min_minutes.is_some() || options.max_minutes.is_some() {
+        parts.push(format!("+{}:[{} TO {}]", SCHEMA_MINUTES, options.min_minutes.unwrap_or(0), options.max_minutes.unwrap_or(i32::MAX)))
+    }
+    if let Some(difficulty) = options.max_difficulty() {
+        parts.push(format!("+{}:[{} TO {}]", SCHEMA_DIFFICULTY, EDifficulty::Easy.rank(), difficulty.rank()))
+    }
 
     return parts.join(" ");
 
 }
 
 
-fn build_season_term(options: SearchPrefill, season_names: HashMap<usize, ESeason>) -> Option<String> {
-    let raw_vals: Vec<Option<i32>> = vec![options.season1, options.season2, options.season3, options.season4, options.season5];
-
-    let season_names: Vec<String> = raw_vals.iter().enumerate()
-        .filter(|x| x.1.is_some())
-        .map(|x| x.0 + 1)
-        .map(|x| x)
-        .map(|x| season_names.get(&(x)))
-        .filter(|x| x.is_some())
-        .map(|x| x.unwrap())
-        .map(|x| format!("/season/{}", x.to_string()))
+fn build_season_term(options: SearchPrefill, seasons: &[Season]) -> Option<String> {
+    let season_facets: Vec<String> = seasons.iter()
+        .filter(|x| options.has_season(x.season_id))
+        .map(|x| format!("/season/{}", x.season_id))
         .collect();
-    if season_names.is_empty() {
+    if season_facets.is_empty() {
         return None;
     }
 
-    let inner: String = season_names.into_iter().join(" ");
+    let inner: String = season_facets.into_iter().join(" ");
     return Some(format!("+season: IN [{}]", inner));
 }
 
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use itertools::assert_equal;
 
     use crate::args::SearchPrefill;
-    use crate::parsetypes::ESeason;
-    use crate::text_search::build_season_term;
+    use crate::seasonality::Season;
+    use crate::search::fuzzy::analyze;
+    use crate::search::language::ERecipeLanguage;
+    use crate::text_search::{build_filter_query, build_season_term, setup_search_state};
+
+    fn seasons() -> Vec<Season> {
+        ["Summer", "Autumn", "Winter", "Spring", "Independent"].iter().enumerate()
+            .map(|(i, x)| Season { season_id: i as i32 + 1, name: x.to_string(), months: None, all_year: false })
+            .collect()
+    }
 
     #[test]
     fn test_season_empty() {
@@ -187,59 +329,109 @@ mod tests {
             course: None,
             book: None,
             tried: 0,
-            season1: None,
-            season2: None,
-            season3: None,
-            season4: None,
-            season5: None,
+            seasons: vec![],
             legacy: None,
+            fuzzy: None,
+            collection: None,
+            tag: None,
+            vegetarian: None,
+            vegan: None,
+            gluten_free: None,
+            dairy_free: None,
+            nut_free: None,
+            sort: None,
+            min_minutes: None,
+            max_minutes: None,
+            max_difficulty: None,
+            equipped: None,
+            max_cost: None,
         };
-        let season_names = ESeason::to_map();
-        let res = build_season_term(options, season_names);
+        let res = build_season_term(options, &seasons());
         assert_eq!(None, res);
     }
 
     #[test]
     fn test_all_seasons() {
-        let seasons = ESeason::get_seasons();
         let options: SearchPrefill = SearchPrefill {
             name: None,
             season: None,
             course: None,
             book: None,
             tried: 0,
-            season1: Some(1),
-            season2: Some(1),
-            season3: Some(1),
-            season4: Some(1),
-            season5: Some(1),
+            seasons: vec![1, 2, 3, 4, 5],
             legacy: None,
+            fuzzy: None,
+            collection: None,
+            tag: None,
+            vegetarian: None,
+            vegan: None,
+            gluten_free: None,
+            dairy_free: None,
+            nut_free: None,
+            sort: None,
+            min_minutes: None,
+            max_minutes: None,
+            max_difficulty: None,
+            equipped: None,
+            max_cost: None,
         };
-        let season_names = ESeason::to_map();
-        let res = build_season_term(options, season_names);
+        let res = build_season_term(options, &seasons());
         assert!(res.is_some());
-        assert_equal("+season: IN [/season/summer /season/autumn /season/winter /season/spring /season/independent]".to_string().bytes(), res.unwrap().bytes());
+        assert_equal("+season: IN [/season/1 /season/2 /season/3 /season/4 /season/5]".to_string().bytes(), res.unwrap().bytes());
     }
 
     #[test]
     fn test_some_seasons() {
-        let seasons = ESeason::get_seasons();
         let options: SearchPrefill = SearchPrefill {
             name: None,
             season: None,
             course: None,
             book: None,
             tried: 0,
-            season1: Some(1),
-            season2: None,
-            season3: Some(1),
-            season4: Some(1),
-            season5: None,
+            seasons: vec![1, 3, 4, 42],
             legacy: None,
+            fuzzy: None,
+            collection: None,
+            tag: None,
+            vegetarian: None,
+            vegan: None,
+            gluten_free: None,
+            dairy_free: None,
+            nut_free: None,
+            sort: None,
+            min_minutes: None,
+            max_minutes: None,
+            max_difficulty: None,
+            equipped: None,
+            max_cost: None,
         };
-        let season_names = ESeason::to_map();
-        let res = build_season_term(options, season_names);
+        let res = build_season_term(options, &seasons());
         assert!(res.is_some());
-        assert_equal("+season: IN [/season/summer /season/winter /season/spring]".to_string().bytes(), res.unwrap().bytes());
+        assert_equal("+season: IN [/season/1 /season/3 /season/4]".to_string().bytes(), res.unwrap().bytes());
+    }
+
+    #[test]
+    fn test_diet_filter() {
+        let options = SearchPrefill::from_query_string("vegan=1&nut_free=1").unwrap();
+        let res = build_filter_query(options, HashMap::new(), &seasons(), HashMap::new());
+        assert_eq!("+diet:/diet/vegan +diet:/diet/nut_free", res);
+    }
+
+    #[test]
+    fn test_time_filter() {
+        let options = SearchPrefill::from_query_string("max_minutes=30&max_difficulty=medium").unwrap();
+        let res = build_filter_query(options, HashMap::new(), &seasons(), HashMap::new());
+        assert_eq!("+minutes:[0 TO 30] +difficulty:[1 TO 2]", res);
+        let options = SearchPrefill::from_query_string("min_minutes=60&max_difficulty=unknown").unwrap();
+        assert_eq!("+minutes:[60 TO 2147483647]", build_filter_query(options, HashMap::new(), &seasons(), HashMap::new()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn synonyms_in_every_language() {
+        let search_state = setup_search_state(vec![vec!["Paradeiser".to_string(), "Tomaten".to_string()]]).unwrap();
+        let german = |text: &str| analyze(&search_state.index, ERecipeLanguage::German.tokenizer_name().as_str(), text);
+        assert_eq!(german("Paradeiser"), german("Tomaten"));
+        let english = |text: &str| analyze(&search_state.index, ERecipeLanguage::English.tokenizer_name().as_str(), text);
+        assert_eq!(english("Paradeiser"), english("Tomaten"));
+    }
+}
@@ -0,0 +1,180 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use dotenvy::dotenv;
+use image::ImageResult;
+use sha2::{Digest, Sha256};
+
+pub const MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+
+const IMAGE_EXTENSIONS: [&str; 5] = ["jpg", "jpeg", "png", "webp", "gif"];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EThumbnailSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl EThumbnailSize {
+    pub fn get_sizes() -> Vec<EThumbnailSize> {
+        vec![EThumbnailSize::Small, EThumbnailSize::Medium, EThumbnailSize::Large]
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            EThumbnailSize::Small => "s",
+            EThumbnailSize::Medium => "m",
+            EThumbnailSize::Large => "l",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<EThumbnailSize> {
+        EThumbnailSize::get_sizes().into_iter().find(|x| x.code() == code)
+    }
+
+    /// Longest edge in pixels.
+    pub fn pixels(&self) -> u32 {
+        match self {
+            EThumbnailSize::Small => 120,
+            EThumbnailSize::Medium => 400,
+            EThumbnailSize::Large => 1200,
+        }
+    }
+}
+
+pub fn upload_dir() -> PathBuf {
+    dotenv().ok();
+    PathBuf::from(env::var("UPLOAD_DIR").unwrap_or("uploads".to_string()))
+}
+
+pub fn image_extension(file_name: &str) -> Option<String> {
+    let (_, extension) = file_name.rsplit_once('.')?;
+    let extension = extension.to_lowercase();
+    IMAGE_EXTENSIONS.contains(&extension.as_str()).then_some(extension)
+}
+
+/// Stored names are the content hash, so uploading the same photo twice keeps a single file.
+pub fn stored_name(content: &[u8], extension: &str) -> String {
+    format!("{}.{}", hex::encode(Sha256::digest(content)), extension)
+}
+
+/// Thumbnails are always jpeg, named after the original so they can be found without a lookup.
+pub fn thumbnail_name(name: &str, size: EThumbnailSize) -> String {
+    let hash = name.split_once('.').map(|x| x.0).unwrap_or(name);
+    format!("{}-{}.jpg", hash, size.code())
+}
+
+/// Splits a thumbnail name into the size and the hash of its original.
+pub fn parse_thumbnail_name(name: &str) -> Option<(String, EThumbnailSize)> {
+    let (stem, extension) = name.split_once('.')?;
+    let (hash, code) = stem.split_once('-')?;
+    if extension != "jpg" || !is_hash(hash) {
+        return None;
+    }
+    Some((hash.to_string(), EThumbnailSize::from_code(code)?))
+}
+
+fn is_hash(a: &str) -> bool {
+    a.len() == 64 && a.chars().all(|x| x.is_ascii_hexdigit())
+}
+
+/// Only names produced by `stored_name` are served, anything else could point outside the upload directory.
+pub fn is_stored_name(name: &str) -> bool {
+    match name.split_once('.') {
+        Some((hash, extension)) => is_hash(hash) && IMAGE_EXTENSIONS.contains(&extension),
+        None => false,
+    }
+}
+
+/// Finds the stored original a thumbnail was made from, the extension is not part of the thumbnail name.
+pub fn find_original(hash: &str) -> Option<String> {
+    IMAGE_EXTENSIONS.iter()
+        .map(|x| format!("{}.{}", hash, x))
+        .find(|x| upload_dir().join(x).exists())
+}
+
+/// Smaller images are copied as they are instead of being scaled up.
+pub fn generate_thumbnails(name: &str) -> ImageResult<()> {
+    let dir = upload_dir();
+    let original = image::open(dir.join(name))?;
+    for size in EThumbnailSize::get_sizes() {
+        let thumbnail = if original.width() > size.pixels() || original.height() > size.pixels() {
+            original.thumbnail(size.pixels(), size.pixels())
+        } else {
+            original.clone()
+        };
+        thumbnail.into_rgb8().save_with_format(dir.join(thumbnail_name(name, size)), image::ImageFormat::Jpeg)?;
+    }
+    Ok(())
+}
+
+pub fn content_type(name: &str) -> &'static str {
+    match image_extension(name).as_deref() {
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        _ => "image/jpeg",
+    }
+}
+
+/// Writes the upload and its thumbnails, `None` when it is not an image we can read.
+pub fn store_image(file_name: &str, content: &[u8]) -> io::Result<Option<String>> {
+    let Some(extension) = image_extension(file_name) else { return Ok(None) };
+    let name = stored_name(content, extension.as_str());
+    let dir = upload_dir();
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(&name), content)?;
+    if generate_thumbnails(name.as_str()).is_err() {
+        remove_image(name.as_str())?;
+        return Ok(None);
+    }
+    Ok(Some(name))
+}
+
+pub fn remove_image(name: &str) -> io::Result<()> {
+    if !is_stored_name(name) {
+        return Ok(());
+    }
+    let dir = upload_dir();
+    for size in EThumbnailSize::get_sizes() {
+        let thumbnail = dir.join(thumbnail_name(name, size));
+        if thumbnail.exists() {
+            fs::remove_file(thumbnail)?;
+        }
+    }
+    fs::remove_file(dir.join(name))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::upload::{image_extension, is_stored_name, parse_thumbnail_name, stored_name, thumbnail_name, EThumbnailSize};
+
+    #[test]
+    fn only_images() {
+        assert_eq!(Some("jpg".to_string()), image_extension("Dinner.JPG"));
+        assert_eq!(None, image_extension("notes.txt"));
+        assert_eq!(None, image_extension("jpg"));
+    }
+
+    #[test]
+    fn stored_names_are_safe() {
+        let name = stored_name(b"photo", "png");
+        assert!(is_stored_name(name.as_str()));
+        assert!(!is_stored_name("../secret.png"));
+        assert!(!is_stored_name(format!("{}.exe", &name[..64]).as_str()));
+    }
+
+    #[test]
+    fn thumbnail_names_round_trip() {
+        let name = stored_name(b"photo", "png");
+        let thumbnail = thumbnail_name(name.as_str(), EThumbnailSize::Medium);
+        assert_eq!(Some((name[..64].to_string(), EThumbnailSize::Medium)), parse_thumbnail_name(thumbnail.as_str()));
+        assert!(!is_stored_name(thumbnail.as_str()));
+        assert_eq!(None, parse_thumbnail_name(name.as_str()));
+        assert_eq!(None, parse_thumbnail_name("../../x-m.jpg"));
+    }
+}